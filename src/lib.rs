@@ -1,4 +1,4 @@
-mod math;
+pub mod math;
 mod ring;
 
 
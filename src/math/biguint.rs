@@ -0,0 +1,775 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign};
+use std::str::FromStr;
+
+/// An unsigned integer of arbitrary size stored as little-endian 64 bit limbs.
+///
+/// The limbs are always kept normalised so that the most significant limb is non-zero.
+/// Zero is represented by an empty vector which means equality and hashing can be derived.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    limbs: Vec<u64>,
+}
+
+/// Returned when a string or byte slice cannot be parsed into a BigUint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseBigUintError {
+    /// The input contained no digits
+    Empty,
+    /// The input contained a character which is not a digit in the requested radix
+    InvalidDigit(char),
+    /// The requested radix is outside of the supported range 2..=36
+    InvalidRadix(u32),
+}
+
+impl fmt::Display for ParseBigUintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseBigUintError::Empty => write!(f, "cannot parse integer from empty string"),
+            ParseBigUintError::InvalidDigit(c) => write!(f, "invalid digit '{}' found in string", c),
+            ParseBigUintError::InvalidRadix(r) => write!(f, "radix {} is not in the range 2..=36", r),
+        }
+    }
+}
+
+impl std::error::Error for ParseBigUintError {}
+
+impl BigUint {
+    /// Returns zero
+    pub fn zero() -> Self {
+        BigUint { limbs: Vec::new() }
+    }
+
+    /// Returns one
+    pub fn one() -> Self {
+        BigUint { limbs: vec![1] }
+    }
+
+    /// Builds a BigUint from little-endian limbs, removing any leading zero limbs
+    pub fn from_limbs(limbs: Vec<u64>) -> Self {
+        let mut result = BigUint { limbs };
+        result.normalise();
+        result
+    }
+
+    /// Returns the little-endian limbs
+    pub fn limbs(&self) -> &[u64] {
+        &self.limbs
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.limbs.len() == 1 && self.limbs[0] == 1
+    }
+
+    pub fn is_even(&self) -> bool {
+        self.limbs.first().is_none_or(|l| l & 1 == 0)
+    }
+
+    pub fn is_odd(&self) -> bool {
+        !self.is_even()
+    }
+
+    /// Returns the number of bits required to represent the value, zero requires zero bits
+    pub fn bits(&self) -> usize {
+        match self.limbs.last() {
+            None => 0,
+            Some(top) => self.limbs.len() * 64 - top.leading_zeros() as usize,
+        }
+    }
+
+    /// Returns the value of bit i where bit 0 is the least significant bit
+    pub fn bit(&self, i: usize) -> bool {
+        self.limbs.get(i / 64).is_some_and(|l| (l >> (i % 64)) & 1 == 1)
+    }
+
+    /// Sets or clears bit i, growing the number if required
+    pub fn set_bit(&mut self, i: usize, value: bool) {
+        let limb = i / 64;
+        if value {
+            if limb >= self.limbs.len() {
+                self.limbs.resize(limb + 1, 0);
+            }
+            self.limbs[limb] |= 1 << (i % 64);
+        } else if limb < self.limbs.len() {
+            self.limbs[limb] &= !(1 << (i % 64));
+            self.normalise();
+        }
+    }
+
+    /// Returns the number of trailing zero bits or None if the value is zero
+    pub fn trailing_zeros(&self) -> Option<usize> {
+        let i = self.limbs.iter().position(|&l| l != 0)?;
+        Some(i * 64 + self.limbs[i].trailing_zeros() as usize)
+    }
+
+    /// Returns the value as a u64 if it fits
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs.len() {
+            0 => Some(0),
+            1 => Some(self.limbs[0]),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a u128 if it fits
+    pub fn to_u128(&self) -> Option<u128> {
+        match self.limbs.len() {
+            0 => Some(0),
+            1 => Some(self.limbs[0] as u128),
+            2 => Some((self.limbs[1] as u128) << 64 | self.limbs[0] as u128),
+            _ => None,
+        }
+    }
+
+    /// Parses big-endian bytes, leading zero bytes are allowed
+    pub fn from_bytes_be(bytes: &[u8]) -> Self {
+        let limbs = bytes
+            .rchunks(8)
+            .map(|chunk| chunk.iter().fold(0u64, |acc, &b| acc << 8 | b as u64))
+            .collect();
+        BigUint::from_limbs(limbs)
+    }
+
+    /// Returns the minimal big-endian byte representation, zero is encoded as a single zero byte
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        if self.is_zero() {
+            return vec![0];
+        }
+        let bytes: Vec<u8> = self.limbs.iter().rev().flat_map(|l| l.to_be_bytes()).collect();
+        let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+        bytes[start..].to_vec()
+    }
+
+    /// Parses a string of digits in the given radix (2 to 36). Underscores are ignored.
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseBigUintError> {
+        if !(2..=36).contains(&radix) {
+            return Err(ParseBigUintError::InvalidRadix(radix));
+        }
+
+        let mut result = BigUint::zero();
+        let mut empty = true;
+        for c in s.chars().filter(|&c| c != '_') {
+            let digit = c.to_digit(radix).ok_or(ParseBigUintError::InvalidDigit(c))?;
+            result.mul_add_small(radix as u64, digit as u64);
+            empty = false;
+        }
+
+        if empty {
+            return Err(ParseBigUintError::Empty);
+        }
+        Ok(result)
+    }
+
+    /// Parses a hexadecimal string with an optional 0x prefix
+    pub fn from_hex(s: &str) -> Result<Self, ParseBigUintError> {
+        let s = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")).unwrap_or(s);
+        BigUint::from_str_radix(s, 16)
+    }
+
+    /// Returns the lowercase hexadecimal representation without a prefix
+    pub fn to_hex(&self) -> String {
+        format!("{:x}", self)
+    }
+
+    /// Subtracts rhs returning None if the result would be negative
+    pub fn checked_sub(&self, rhs: &BigUint) -> Option<BigUint> {
+        if *self < *rhs {
+            return None;
+        }
+        let mut limbs = self.limbs.clone();
+        sub_assign_limbs(&mut limbs, &rhs.limbs);
+        Some(BigUint::from_limbs(limbs))
+    }
+
+    /// Returns the quotient and remainder of dividing self by divisor
+    pub fn div_rem(&self, divisor: &BigUint) -> (BigUint, BigUint) {
+        if divisor.is_zero() {
+            panic!("attempt to divide by zero")
+        }
+        if *self < *divisor {
+            return (BigUint::zero(), self.clone());
+        }
+        if divisor.limbs.len() == 1 {
+            let (q, r) = div_rem_small(&self.limbs, divisor.limbs[0]);
+            return (BigUint::from_limbs(q), BigUint::from(r));
+        }
+
+        let (q, r) = div_rem_knuth(&self.limbs, &divisor.limbs);
+        (BigUint::from_limbs(q), BigUint::from_limbs(r))
+    }
+
+    /// Raises self to the power of exponent
+    pub fn pow(&self, mut exponent: u32) -> BigUint {
+        let mut base = self.clone();
+        let mut result = BigUint::one();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = &result * &base;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    /// Computes self = self * m + a for small values m and a
+    fn mul_add_small(&mut self, m: u64, a: u64) {
+        let mut carry = a as u128;
+        for limb in self.limbs.iter_mut() {
+            let t = *limb as u128 * m as u128 + carry;
+            *limb = t as u64;
+            carry = t >> 64;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u64);
+        }
+        self.normalise();
+    }
+
+    fn normalise(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+}
+
+/// Adds b into a in place, growing a if required
+fn add_assign_limbs(a: &mut Vec<u64>, b: &[u64]) {
+    if a.len() < b.len() {
+        a.resize(b.len(), 0);
+    }
+    let mut carry = false;
+    for (i, limb) in a.iter_mut().enumerate() {
+        if i >= b.len() && !carry {
+            break;
+        }
+        let (s1, c1) = limb.overflowing_add(*b.get(i).unwrap_or(&0));
+        let (s2, c2) = s1.overflowing_add(carry as u64);
+        *limb = s2;
+        carry = c1 || c2;
+    }
+    if carry {
+        a.push(1);
+    }
+}
+
+/// Subtracts b from a in place, the caller must ensure a >= b
+fn sub_assign_limbs(a: &mut [u64], b: &[u64]) {
+    let mut borrow = false;
+    for (i, limb) in a.iter_mut().enumerate() {
+        if i >= b.len() && !borrow {
+            break;
+        }
+        let (s1, b1) = limb.overflowing_sub(*b.get(i).unwrap_or(&0));
+        let (s2, b2) = s1.overflowing_sub(borrow as u64);
+        *limb = s2;
+        borrow = b1 || b2;
+    }
+    debug_assert!(!borrow, "subtraction underflow");
+}
+
+/// Schoolbook multiplication of two limb slices
+fn mul_limbs(a: &[u64], b: &[u64]) -> Vec<u64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut result = vec![0u64; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, &y) in b.iter().enumerate() {
+            let t = x as u128 * y as u128 + result[i + j] as u128 + carry;
+            result[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        result[i + b.len()] = carry as u64;
+    }
+    result
+}
+
+/// Divides a limb slice by a single limb returning the quotient limbs and remainder
+fn div_rem_small(a: &[u64], d: u64) -> (Vec<u64>, u64) {
+    let mut q = vec![0u64; a.len()];
+    let mut r = 0u128;
+    for i in (0..a.len()).rev() {
+        let t = r << 64 | a[i] as u128;
+        q[i] = (t / d as u128) as u64;
+        r = t % d as u128;
+    }
+    (q, r as u64)
+}
+
+/// Long division using Knuth's Algorithm D (The Art of Computer Programming, Vol 2, 4.3.1).
+/// Requires u >= v and v to have at least two limbs.
+fn div_rem_knuth(u: &[u64], v: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    let n = v.len();
+    let m = u.len() - n;
+
+    // normalise so that the top limb of the divisor has its highest bit set
+    let shift = v[n - 1].leading_zeros();
+    let vn = shl_limbs(v, shift as usize);
+    let mut un = shl_limbs(u, shift as usize);
+    un.resize(u.len() + 1, 0);
+
+    let mut q = vec![0u64; m + 1];
+    let base = 1u128 << 64;
+
+    for j in (0..=m).rev() {
+        // estimate the quotient digit from the top two limbs
+        let num = (un[j + n] as u128) << 64 | un[j + n - 1] as u128;
+        let mut qhat = num / vn[n - 1] as u128;
+        let mut rhat = num % vn[n - 1] as u128;
+        while qhat >= base || qhat * vn[n - 2] as u128 > (rhat << 64 | un[j + n - 2] as u128) {
+            qhat -= 1;
+            rhat += vn[n - 1] as u128;
+            if rhat >= base {
+                break;
+            }
+        }
+
+        // multiply and subtract
+        let mut borrow = 0u64;
+        let mut carry = 0u64;
+        for i in 0..n {
+            let p = qhat * vn[i] as u128 + carry as u128;
+            carry = (p >> 64) as u64;
+            let (s1, b1) = un[i + j].overflowing_sub(p as u64);
+            let (s2, b2) = s1.overflowing_sub(borrow);
+            un[i + j] = s2;
+            borrow = b1 as u64 + b2 as u64;
+        }
+        let (s1, b1) = un[j + n].overflowing_sub(carry);
+        let (s2, b2) = s1.overflowing_sub(borrow);
+        un[j + n] = s2;
+
+        // the estimate was one too large so add the divisor back
+        if b1 || b2 {
+            qhat -= 1;
+            let mut c = 0u128;
+            for i in 0..n {
+                let s = un[i + j] as u128 + vn[i] as u128 + c;
+                un[i + j] = s as u64;
+                c = s >> 64;
+            }
+            un[j + n] = un[j + n].wrapping_add(c as u64);
+        }
+
+        q[j] = qhat as u64;
+    }
+
+    un.truncate(n);
+    let r = shr_limbs(&un, shift as usize);
+    (q, r)
+}
+
+fn shl_limbs(a: &[u64], shift: usize) -> Vec<u64> {
+    let limb_shift = shift / 64;
+    let bit_shift = shift % 64;
+    let mut result = vec![0u64; a.len() + limb_shift + 1];
+    for (i, &l) in a.iter().enumerate() {
+        result[i + limb_shift] |= l << bit_shift;
+        if bit_shift > 0 {
+            result[i + limb_shift + 1] |= l >> (64 - bit_shift);
+        }
+    }
+    while result.last() == Some(&0) {
+        result.pop();
+    }
+    result
+}
+
+fn shr_limbs(a: &[u64], shift: usize) -> Vec<u64> {
+    let limb_shift = shift / 64;
+    let bit_shift = shift % 64;
+    if limb_shift >= a.len() {
+        return Vec::new();
+    }
+    let mut result = vec![0u64; a.len() - limb_shift];
+    for i in 0..result.len() {
+        result[i] = a[i + limb_shift] >> bit_shift;
+        if bit_shift > 0 && i + limb_shift + 1 < a.len() {
+            result[i] |= a[i + limb_shift + 1] << (64 - bit_shift);
+        }
+    }
+    result
+}
+
+impl From<u8> for BigUint {
+    fn from(value: u8) -> Self {
+        BigUint::from(value as u64)
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(value: u32) -> Self {
+        BigUint::from(value as u64)
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        BigUint::from_limbs(vec![value])
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(value: u128) -> Self {
+        BigUint::from_limbs(vec![value as u64, (value >> 64) as u64])
+    }
+}
+
+impl From<usize> for BigUint {
+    fn from(value: usize) -> Self {
+        BigUint::from(value as u64)
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigUintError;
+
+    /// Parses a decimal string
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BigUint::from_str_radix(s, 10)
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return f.pad_integral(true, "", "0");
+        }
+
+        // peel off 19 decimal digits at a time which is the largest power of ten in a u64
+        const CHUNK: u64 = 10_000_000_000_000_000_000;
+        let mut chunks = Vec::new();
+        let mut limbs = self.limbs.clone();
+        while !limbs.is_empty() {
+            let (q, r) = div_rem_small(&limbs, CHUNK);
+            chunks.push(r);
+            limbs = q;
+            while limbs.last() == Some(&0) {
+                limbs.pop();
+            }
+        }
+
+        let mut s = chunks.pop().unwrap().to_string();
+        for chunk in chunks.iter().rev() {
+            s.push_str(&format!("{:019}", chunk));
+        }
+        f.pad_integral(true, "", &s)
+    }
+}
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::LowerHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = String::new();
+        match self.limbs.split_last() {
+            None => s.push('0'),
+            Some((top, rest)) => {
+                s.push_str(&format!("{:x}", top));
+                for l in rest.iter().rev() {
+                    s.push_str(&format!("{:016x}", l));
+                }
+            }
+        }
+        f.pad_integral(true, "0x", &s)
+    }
+}
+
+impl fmt::UpperHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = format!("{:x}", self).to_uppercase();
+        f.pad_integral(true, "0x", &s)
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, rhs: &BigUint) -> BigUint {
+        let mut limbs = self.limbs.clone();
+        add_assign_limbs(&mut limbs, &rhs.limbs);
+        BigUint { limbs }
+    }
+}
+
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, rhs: &BigUint) -> BigUint {
+        self.checked_sub(rhs).expect("attempt to subtract with overflow")
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        BigUint::from_limbs(mul_limbs(&self.limbs, &rhs.limbs))
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).1
+    }
+}
+
+/// Forwards the owned and mixed reference variants of a binary operator to the
+/// implementation on two references
+macro_rules! forward_binop {
+    ($imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident) => {
+        impl $imp<BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, rhs: BigUint) -> BigUint {
+                (&self).$method(&rhs)
+            }
+        }
+
+        impl $imp<&BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, rhs: &BigUint) -> BigUint {
+                (&self).$method(rhs)
+            }
+        }
+
+        impl $imp<BigUint> for &BigUint {
+            type Output = BigUint;
+
+            fn $method(self, rhs: BigUint) -> BigUint {
+                self.$method(&rhs)
+            }
+        }
+
+        impl $imp_assign<BigUint> for BigUint {
+            fn $method_assign(&mut self, rhs: BigUint) {
+                *self = (&*self).$method(&rhs);
+            }
+        }
+
+        impl $imp_assign<&BigUint> for BigUint {
+            fn $method_assign(&mut self, rhs: &BigUint) {
+                *self = (&*self).$method(rhs);
+            }
+        }
+    };
+}
+
+forward_binop!(Add, add, AddAssign, add_assign);
+forward_binop!(Sub, sub, SubAssign, sub_assign);
+forward_binop!(Mul, mul, MulAssign, mul_assign);
+forward_binop!(Div, div, DivAssign, div_assign);
+forward_binop!(Rem, rem, RemAssign, rem_assign);
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shl(self, shift: usize) -> BigUint {
+        BigUint { limbs: shl_limbs(&self.limbs, shift) }
+    }
+}
+
+impl Shl<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shl(self, shift: usize) -> BigUint {
+        &self << shift
+    }
+}
+
+impl ShlAssign<usize> for BigUint {
+    fn shl_assign(&mut self, shift: usize) {
+        *self = &*self << shift;
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shr(self, shift: usize) -> BigUint {
+        BigUint::from_limbs(shr_limbs(&self.limbs, shift))
+    }
+}
+
+impl Shr<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shr(self, shift: usize) -> BigUint {
+        &self >> shift
+    }
+}
+
+impl ShrAssign<usize> for BigUint {
+    fn shr_assign(&mut self, shift: usize) {
+        *self = &*self >> shift;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use ring::rand::{SecureRandom, SystemRandom};
+
+    fn random_biguint(rng: &SystemRandom, max_bytes: usize) -> BigUint {
+        let mut len = [0u8; 1];
+        rng.fill(&mut len).unwrap();
+        let mut bytes = vec![0u8; 1 + len[0] as usize % max_bytes];
+        rng.fill(&mut bytes).unwrap();
+        BigUint::from_bytes_be(&bytes)
+    }
+
+    #[test]
+    fn run_arithmetic_matches_u128() {
+        let values: [u128; 6] = [0, 1, 0xffff_ffff, u64::MAX as u128, 0x1234_5678_9abc_def0_1122_3344, u128::MAX >> 1];
+
+        for &a in &values {
+            for &b in &values {
+                let (x, y) = (BigUint::from(a), BigUint::from(b));
+                assert_eq!((&x + &y).to_u128(), Some(a + b));
+                if a >= b {
+                    assert_eq!((&x - &y).to_u128(), Some(a - b));
+                } else {
+                    assert_eq!(x.checked_sub(&y), None);
+                }
+                if let Some(p) = a.checked_mul(b) {
+                    assert_eq!((&x * &y).to_u128(), Some(p));
+                }
+                if let Some(q) = a.checked_div(b) {
+                    assert_eq!((&x / &y).to_u128(), Some(q));
+                    assert_eq!((&x % &y).to_u128(), Some(a % b));
+                }
+                assert_eq!(x.cmp(&y), a.cmp(&b));
+            }
+        }
+    }
+
+    #[test]
+    fn run_large_values() {
+        // (2^256 - 1)^2 = 2^512 - 2^257 + 1
+        let max256 = (BigUint::one() << 256) - BigUint::one();
+        let square = &max256 * &max256;
+        let expected = (BigUint::one() << 512) - (BigUint::one() << 257) + BigUint::one();
+        assert_eq!(square, expected);
+        assert_eq!(square.bits(), 512);
+        assert_eq!(&square / &max256, max256);
+        assert!((&square % &max256).is_zero());
+
+        // 2^128 in decimal
+        let two_128 = BigUint::one() << 128;
+        assert_eq!(two_128.to_string(), "340282366920938463463374607431768211456");
+        assert_eq!(BigUint::from(2u32).pow(128), two_128);
+    }
+
+    #[test]
+    fn run_div_rem_random() {
+        let rng = SystemRandom::new();
+
+        for _ in 0..500 {
+            let u = random_biguint(&rng, 96);
+            let v = random_biguint(&rng, 48);
+            if v.is_zero() {
+                continue;
+            }
+            let (q, r) = u.div_rem(&v);
+            assert!(r < v);
+            assert_eq!(&q * &v + &r, u);
+        }
+    }
+
+    #[test]
+    fn run_div_rem_add_back() {
+        // values at the limb boundaries exercise the quotient digit correction steps
+        let u = BigUint::from_limbs(vec![0, 0, 0x8000_0000_0000_0000, 0x7fff_ffff_ffff_ffff]);
+        let v = BigUint::from_limbs(vec![1, 0, 0x8000_0000_0000_0000]);
+        let (q, r) = u.div_rem(&v);
+        assert!(r < v);
+        assert_eq!(&q * &v + &r, u);
+    }
+
+    #[test]
+    fn run_shifts_and_bits() {
+        let x = BigUint::from_hex("0x1_0000_0000_0000_0001").unwrap();
+        assert_eq!(x.bits(), 65);
+        assert!(x.bit(0) && x.bit(64) && !x.bit(1));
+        assert_eq!(x.trailing_zeros(), Some(0));
+        assert_eq!(&x << 70 >> 70, x);
+        assert_eq!((&x << 3).to_hex(), "80000000000000008");
+        assert_eq!(&x >> 200, BigUint::zero());
+
+        let mut y = BigUint::zero();
+        y.set_bit(130, true);
+        assert_eq!(y, BigUint::one() << 130);
+        assert_eq!(y.trailing_zeros(), Some(130));
+        y.set_bit(130, false);
+        assert!(y.is_zero());
+        assert_eq!(y.trailing_zeros(), None);
+    }
+
+    #[test]
+    fn run_parsing_and_formatting() {
+        let p256 = "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff";
+        let x = BigUint::from_hex(p256).unwrap();
+        assert_eq!(x.to_hex(), p256);
+        assert_eq!(format!("{:#X}", x), format!("0x{}", p256.to_uppercase()));
+        assert_eq!(
+            x.to_string(),
+            "115792089210356248762697446949407573530086143415290314195533631308867097853951"
+        );
+        assert_eq!(x.to_string().parse::<BigUint>().unwrap(), x);
+
+        let bytes = x.to_bytes_be();
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(hex::encode(&bytes), p256);
+        assert_eq!(BigUint::from_bytes_be(&bytes), x);
+        assert_eq!(BigUint::from_bytes_be(&[0, 0, 1, 2]), BigUint::from(0x0102u32));
+        assert_eq!(BigUint::zero().to_bytes_be(), vec![0]);
+
+        assert_eq!(BigUint::from_str_radix("101", 2).unwrap(), BigUint::from(5u32));
+        assert_eq!(BigUint::from_str_radix("zz", 36).unwrap(), BigUint::from(1295u32));
+        assert_eq!("".parse::<BigUint>(), Err(ParseBigUintError::Empty));
+        assert_eq!("12a".parse::<BigUint>(), Err(ParseBigUintError::InvalidDigit('a')));
+        assert_eq!(BigUint::from_str_radix("1", 40), Err(ParseBigUintError::InvalidRadix(40)));
+        assert_eq!(format!("{:>5}", BigUint::from(42u32)), "   42");
+    }
+
+}
@@ -0,0 +1,254 @@
+use crate::math::euclid;
+use crate::math::group::{AbelianGroup, Addition, Group, Monoid, Multiplication};
+use crate::math::integer::UnsignedInt;
+use crate::math::ring::{NaturalNumbersModN, Ring};
+use crate::math::Error;
+
+/// Solves the system of congruences x = a_i mod m_i given as (a_i, m_i) pairs using the
+/// Chinese Remainder Theorem.
+///
+/// Returns the solution x along with the modulus it is unique to, which is the lcm of the m_i.
+/// The moduli do not need to be coprime but when they share a factor the residues must agree
+/// modulo that factor. Returns Overflow if the combined modulus does not fit in E.
+pub fn crt<E: UnsignedInt>(congruences: &[(E, E)]) -> Result<(E, E), Error> {
+    let mut x = E::zero();
+    let mut m = E::one();
+
+    for (a, n) in congruences {
+        if n.is_zero() {
+            return Err(Error::ZeroModulus);
+        }
+
+        // x + m t = a mod n has a solution t only when gcd(m, n) divides a - x
+        let g = euclid::gcd(m.clone(), n.clone());
+        let diff = a.sub_mod(&x, n);
+        let (diff_g, r) = diff.div_rem(&g);
+        if !r.is_zero() {
+            return Err(Error::InconsistentCongruences);
+        }
+
+        // t = (a - x) / g * (m / g)^-1 mod (n / g)
+        let n_g = n.clone() / g.clone();
+        let m_g = m.clone() / g;
+        let inverse = euclid::mod_inverse(m_g, n_g.clone()).ok_or(Error::NotInvertible)?;
+        let t = diff_g.mul_mod(&inverse, &n_g);
+
+        // x + m t is below the new modulus m * n / g so fits whenever it does
+        let lcm = m.checked_mul(&n_g).ok_or(Error::Overflow)?;
+        x = x + m * t;
+        m = lcm;
+    }
+
+    Ok((x, m))
+}
+
+/// The product of two rings where addition and multiplication act on each component separately
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProductRing<A, B>(pub A, pub B);
+
+impl<A, B, E1, E2> Monoid<Addition, (E1, E2)> for ProductRing<A, B>
+where
+    A: Monoid<Addition, E1>,
+    B: Monoid<Addition, E2>,
+{
+    fn apply(&self, e1: (E1, E2), e2: (E1, E2)) -> (E1, E2) {
+        (self.0.apply(e1.0, e2.0), self.1.apply(e1.1, e2.1))
+    }
+
+    fn identity(&self) -> (E1, E2) {
+        (self.0.identity(), self.1.identity())
+    }
+}
+
+impl<A, B, E1, E2> Group<Addition, (E1, E2)> for ProductRing<A, B>
+where
+    A: Group<Addition, E1>,
+    B: Group<Addition, E2>,
+{
+    fn inverse(&self, e: (E1, E2)) -> (E1, E2) {
+        (self.0.inverse(e.0), self.1.inverse(e.1))
+    }
+}
+
+impl<A, B, E1, E2> AbelianGroup<Addition, (E1, E2)> for ProductRing<A, B>
+where
+    A: AbelianGroup<Addition, E1>,
+    B: AbelianGroup<Addition, E2>,
+{
+}
+
+impl<A, B, E1, E2> Monoid<Multiplication, (E1, E2)> for ProductRing<A, B>
+where
+    A: Monoid<Multiplication, E1>,
+    B: Monoid<Multiplication, E2>,
+{
+    fn apply(&self, e1: (E1, E2), e2: (E1, E2)) -> (E1, E2) {
+        (self.0.apply(e1.0, e2.0), self.1.apply(e1.1, e2.1))
+    }
+
+    fn identity(&self) -> (E1, E2) {
+        (self.0.identity(), self.1.identity())
+    }
+}
+
+impl<A: Ring<E1>, B: Ring<E2>, E1, E2> Ring<(E1, E2)> for ProductRing<A, B> {}
+
+/// The ring isomorphism Z/mnZ -> Z/mZ x Z/nZ given by the Chinese Remainder Theorem when m and
+/// n are coprime.
+///
+/// Working in the product ring lets a computation mod mn be split into two smaller computations
+/// mod m and mod n, which is how RSA decryption is usually sped up.
+pub struct CrtIsomorphism<E> {
+    source: NaturalNumbersModN<E>,
+    target: ProductRing<NaturalNumbersModN<E>, NaturalNumbersModN<E>>,
+    /// The idempotent which is 1 mod m and 0 mod n
+    e_m: E,
+    /// The idempotent which is 0 mod m and 1 mod n
+    e_n: E,
+}
+
+impl<E: UnsignedInt> CrtIsomorphism<E> {
+    /// Returns an error if either modulus is zero, they are not coprime or the product mn does not
+    /// fit in E.
+    pub fn new(m: E, n: E) -> Result<Self, Error> {
+        let source = NaturalNumbersModN::try_new(m.checked_mul(&n).ok_or(Error::Overflow)?)?;
+        let target = ProductRing(NaturalNumbersModN::try_new(m.clone())?, NaturalNumbersModN::try_new(n.clone())?);
+
+        let mn = source.modulus().clone();
+        let n_inverse = euclid::mod_inverse(n.clone(), m.clone()).ok_or(Error::NotCoprime)?;
+        let m_inverse = euclid::mod_inverse(m.clone(), n.clone()).ok_or(Error::NotCoprime)?;
+        let e_m = n.mul_mod(&n_inverse, &mn);
+        let e_n = m.mul_mod(&m_inverse, &mn);
+
+        Ok(CrtIsomorphism { source, target, e_m, e_n })
+    }
+
+    /// Returns the ring of integers mod mn
+    pub fn source(&self) -> &NaturalNumbersModN<E> {
+        &self.source
+    }
+
+    /// Returns the product of the rings of integers mod m and mod n
+    pub fn target(&self) -> &ProductRing<NaturalNumbersModN<E>, NaturalNumbersModN<E>> {
+        &self.target
+    }
+
+    /// Maps x mod mn to (x mod m, x mod n)
+    pub fn forward(&self, x: E) -> (E, E) {
+        (x.clone() % self.target.0.modulus().clone(), x % self.target.1.modulus().clone())
+    }
+
+    /// Maps (a mod m, b mod n) back to the unique x mod mn with x = a mod m and x = b mod n
+    pub fn backward(&self, (a, b): (E, E)) -> E {
+        let mn = self.source.modulus();
+        a.mul_mod(&self.e_m, mn).add_mod(&b.mul_mod(&self.e_n, mn), mn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::math::laws::{check_ring, Elements};
+    use crate::math::BigUint;
+
+    #[test]
+    fn run_crt() {
+        // Sunzi's original problem
+        assert_eq!(crt(&[(2u32, 3), (3, 5), (2, 7)]), Ok((23, 105)));
+        assert_eq!(crt::<u32>(&[]), Ok((0, 1)));
+        assert_eq!(crt(&[(12u32, 5)]), Ok((2, 5)));
+
+        // moduli sharing a factor with consistent and inconsistent residues
+        assert_eq!(crt(&[(2u32, 4), (4, 6)]), Ok((10, 12)));
+        assert_eq!(crt(&[(1u32, 4), (2, 6)]), Err(Error::InconsistentCongruences));
+        assert_eq!(crt(&[(1u32, 4), (2, 0)]), Err(Error::ZeroModulus));
+
+        // the combined modulus 65537 * 65539 does not fit in a u32 but does in a u64, while a
+        // shared factor only counts once
+        assert_eq!(crt(&[(1u32, 65537), (2, 65539)]), Err(Error::Overflow));
+        assert_eq!(crt(&[(1u64, 65537), (2, 65539)]).map(|(_, m)| m), Ok(65537 * 65539));
+        assert_eq!(crt(&[(1u32, 65536), (1, 65536)]), Ok((1, 65536)));
+
+        // every solution satisfies every congruence
+        for a in 0..8u64 {
+            for b in 0..12u64 {
+                match crt(&[(a, 8), (b, 12)]) {
+                    Ok((x, m)) => {
+                        assert_eq!(m, 24);
+                        assert_eq!((x % 8, x % 12), (a, b));
+                    }
+                    Err(e) => {
+                        assert_eq!(e, Error::InconsistentCongruences);
+                        assert_ne!(a % 4, b % 4);
+                    }
+                }
+            }
+        }
+
+        // large moduli using BigUint
+        let p = BigUint::from(u64::MAX - 58);
+        let q = BigUint::from(u32::MAX - 4);
+        let r = BigUint::from(1_000_000_007u32);
+        let x = BigUint::from_hex("123456789abcdef0123456789abcdef").unwrap();
+        let congruences = [(&x % &p, p.clone()), (&x % &q, q.clone()), (&x % &r, r.clone())];
+        assert_eq!(crt(&congruences), Ok((x, &p * &q * &r)));
+    }
+
+    #[test]
+    fn run_crt_isomorphism() {
+        let iso = CrtIsomorphism::new(4u32, 9).unwrap();
+        assert_eq!(iso.forward(23), (3, 5));
+        assert_eq!(iso.backward((3, 5)), 23);
+
+        // forward and backward are inverse bijections
+        let mut seen = std::collections::HashSet::new();
+        for x in 0..36 {
+            let (a, b) = iso.forward(x);
+            assert!(seen.insert((a, b)));
+            assert_eq!(iso.backward((a, b)), x);
+        }
+
+        // and preserve addition and multiplication
+        for x in 0..36 {
+            for y in 0..36 {
+                let sum = iso.source().add(x, y);
+                assert_eq!(iso.forward(sum), iso.target().add(iso.forward(x), iso.forward(y)));
+                let product = iso.source().multiply(x, y);
+                assert_eq!(iso.forward(product), iso.target().multiply(iso.forward(x), iso.forward(y)));
+            }
+        }
+
+        let elements: Vec<(u32, u32)> = (0..36).map(|x| iso.forward(x)).collect();
+        assert_eq!(check_ring(iso.target(), &mut Elements::All(&elements)), Ok(()));
+
+        assert_eq!(CrtIsomorphism::new(6u32, 9).err(), Some(Error::NotCoprime));
+        assert_eq!(CrtIsomorphism::new(0u32, 9).err(), Some(Error::ZeroModulus));
+        assert_eq!(CrtIsomorphism::new(65537u32, 65539).err(), Some(Error::Overflow));
+    }
+
+    #[test]
+    fn run_rsa_decryption_with_crt() {
+        // toy RSA key with 32 bit primes
+        let p = 4_294_967_291u64;
+        let q = 4_294_967_279u64;
+        let n = p * q;
+        let phi = (p - 1) * (q - 1);
+        let e = 65537u64;
+        let d = euclid::mod_inverse(e, phi).unwrap();
+
+        let message = 0x1234_5678_9abc_def0u64 % n;
+        let ciphertext = message.pow_mod(&e, &n);
+
+        // decrypt mod n directly
+        assert_eq!(ciphertext.pow_mod(&d, &n), message);
+
+        // decrypt in each component with reduced exponents then map back
+        let iso = CrtIsomorphism::new(p, q).unwrap();
+        let (cp, cq) = iso.forward(ciphertext);
+        let mp = cp.pow_mod(&(d % (p - 1)), &p);
+        let mq = cq.pow_mod(&(d % (q - 1)), &q);
+        assert_eq!(iso.backward((mp, mq)), message);
+    }
+
+}
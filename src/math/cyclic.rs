@@ -0,0 +1,254 @@
+use crate::math::factor::factor;
+use crate::math::field::PrimeField;
+use crate::math::group::{Addition, Group, Monoid, Multiplication, Operation};
+use crate::math::integer::UnsignedInt;
+use crate::math::number_theory::primitive_root;
+use crate::math::ring::NaturalNumbersModN;
+use crate::math::Error;
+
+/// A finite group where every element is a power of a single generator g:
+/// Cyclic - every a = g . g . ... . g for some number of copies of g
+///
+/// Every subgroup of a cyclic group is cyclic and there is exactly one subgroup for each divisor
+/// of the order, which is what lets Diffie-Hellman work in a subgroup of large prime order.
+pub trait CyclicGroup<T: Operation, E: Clone + PartialEq>: Group<T, E> {
+    /// The integer type used for the order and exponents
+    type Order: UnsignedInt;
+
+    /// Returns the number of elements
+    fn order(&self) -> Self::Order;
+
+    /// Returns an element which generates the whole group
+    fn generator(&self) -> E;
+
+    /// Reduces e and returns it if it belongs to the group, like UnitsModN::element. Values such
+    /// as zero under multiplication are rejected as their powers never reach the identity.
+    fn element(&self, e: &E) -> Result<E, Error>;
+
+    /// Returns the order of e, the smallest k > 0 with e^k equal to the identity, or an error if e
+    /// does not belong to the group.
+    ///
+    /// The order of an element divides the order of the group so the prime factors of the group
+    /// order are divided out while the power is still the identity.
+    fn order_of(&self, e: &E) -> Result<Self::Order, Error>
+    where
+        Self: Sized,
+    {
+        let e = self.element(e)?;
+        let mut order = self.order();
+        for (q, _) in factor(&order) {
+            loop {
+                let (reduced, r) = order.div_rem(&q);
+                if !r.is_zero() || <Self as Monoid<T, E>>::pow(self, e.clone(), &reduced) != self.identity() {
+                    break;
+                }
+                order = reduced;
+            }
+        }
+        Ok(order)
+    }
+
+    /// Returns true if e generates the whole group, that is e belongs to the group and e^(n/q) is
+    /// not the identity for any prime q dividing the order n
+    fn is_generator(&self, e: &E) -> bool
+    where
+        Self: Sized,
+    {
+        let Ok(e) = self.element(e) else {
+            return false;
+        };
+        let order = self.order();
+        factor(&order).primes().all(|q| {
+            <Self as Monoid<T, E>>::pow(self, e.clone(), &(order.clone() / q.clone())) != self.identity()
+        })
+    }
+
+    /// Returns the elements of the subgroup generated by e in the order the powers of e reach them,
+    /// starting from the identity, or an error if e does not belong to the group as then its
+    /// powers never return to the identity.
+    fn subgroup(&self, e: &E) -> Result<Vec<E>, Error> {
+        let e = self.element(e)?;
+        let identity = self.identity();
+        let mut elements = vec![identity.clone()];
+        let mut power = e.clone();
+        while power != identity {
+            elements.push(power.clone());
+            power = self.apply(power, e.clone());
+        }
+        Ok(elements)
+    }
+
+    /// Returns a generator of the unique subgroup of order q, or None if q does not divide the
+    /// order of the group
+    fn subgroup_generator(&self, q: &Self::Order) -> Option<E>
+    where
+        Self: Sized,
+    {
+        let (cofactor, r) = self.order().div_rem(q);
+        if q.is_zero() || !r.is_zero() {
+            return None;
+        }
+        Some(<Self as Monoid<T, E>>::pow(self, self.generator(), &cofactor))
+    }
+
+    /// Returns true if e belongs to the subgroup of order q, that is e belongs to the group and e^q
+    /// is the identity. This is how a public value received in Diffie-Hellman is checked before it
+    /// is used.
+    fn is_in_subgroup(&self, e: &E, q: &Self::Order) -> bool
+    where
+        Self: Sized,
+    {
+        match self.element(e) {
+            Ok(e) => <Self as Monoid<T, E>>::pow(self, e, q) == self.identity(),
+            Err(_) => false,
+        }
+    }
+}
+
+/// The integers mod n under addition are generated by one
+impl<E: UnsignedInt> CyclicGroup<Addition, E> for NaturalNumbersModN<E> {
+    type Order = E;
+
+    fn order(&self) -> E {
+        self.modulus().clone()
+    }
+
+    fn generator(&self) -> E {
+        E::one() % self.modulus().clone()
+    }
+
+    fn element(&self, e: &E) -> Result<E, Error> {
+        Ok(e.clone() % self.modulus().clone())
+    }
+}
+
+/// The non-zero elements of a prime field under multiplication are generated by a primitive root
+impl<E: UnsignedInt> CyclicGroup<Multiplication, E> for PrimeField<E> {
+    type Order = E;
+
+    fn order(&self) -> E {
+        self.modulus().clone() - E::one()
+    }
+
+    fn generator(&self) -> E {
+        primitive_root(self.modulus()).expect("the units mod a prime are cyclic")
+    }
+
+    /// Zero and its multiples of p are not units
+    fn element(&self, e: &E) -> Result<E, Error> {
+        let e = e.clone() % self.modulus().clone();
+        if e.is_zero() {
+            return Err(Error::DivisionByZero);
+        }
+        Ok(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::math::prime_gen::random_safe_prime;
+    use crate::math::primes::random_below;
+    use ring::rand::SystemRandom;
+
+    #[test]
+    fn run_additive_cyclic_group() {
+        let ring = NaturalNumbersModN::new(12u32);
+        let group = &ring as &dyn CyclicGroup<Addition, u32, Order = u32>;
+        assert_eq!(group.order(), 12);
+        assert_eq!(group.generator(), 1);
+        assert_eq!(group.subgroup(&4), Ok(vec![0, 4, 8]));
+        assert_eq!(group.subgroup(&0), Ok(vec![0]));
+        assert_eq!(group.subgroup(&16), Ok(vec![0, 4, 8]));
+
+        assert_eq!(CyclicGroup::<Addition, u32>::order_of(&ring, &8), Ok(3));
+        assert_eq!(CyclicGroup::<Addition, u32>::order_of(&ring, &0), Ok(1));
+        let generators: Vec<u32> = (0..12).filter(|e| CyclicGroup::<Addition, u32>::is_generator(&ring, e)).collect();
+        assert_eq!(generators, vec![1, 5, 7, 11]);
+
+        assert_eq!(CyclicGroup::<Addition, u32>::subgroup_generator(&ring, &4), Some(3));
+        assert_eq!(CyclicGroup::<Addition, u32>::subgroup_generator(&ring, &5), None);
+        assert!(CyclicGroup::<Addition, u32>::is_in_subgroup(&ring, &9, &4));
+        assert!(!CyclicGroup::<Addition, u32>::is_in_subgroup(&ring, &2, &4));
+
+        // one is the whole group mod one
+        let trivial = NaturalNumbersModN::new(1u32);
+        assert_eq!(CyclicGroup::<Addition, u32>::generator(&trivial), 0);
+        assert_eq!(CyclicGroup::<Addition, u32>::subgroup(&trivial, &0), Ok(vec![0]));
+    }
+
+    #[test]
+    fn run_multiplicative_cyclic_group() {
+        let field = PrimeField::new(23u32);
+        let group = &field as &dyn CyclicGroup<Multiplication, u32, Order = u32>;
+        assert_eq!(group.order(), 22);
+        assert_eq!(group.generator(), 5);
+        assert_eq!(group.subgroup(&22), Ok(vec![1, 22]));
+        assert_eq!(group.subgroup(&5).unwrap().len(), 22);
+
+        // Lagrange's theorem: the order of every element divides the order of the group and
+        // there are φ(d) elements of each order d
+        let mut counts = std::collections::BTreeMap::new();
+        for e in 1..23 {
+            let order = CyclicGroup::<Multiplication, u32>::order_of(&field, &e).unwrap();
+            assert_eq!(order as usize, group.subgroup(&e).unwrap().len());
+            *counts.entry(order).or_insert(0) += 1;
+        }
+        assert_eq!(counts.into_iter().collect::<Vec<_>>(), vec![(1, 1), (2, 1), (11, 10), (22, 10)]);
+
+        let generators = (1..23).filter(|e| CyclicGroup::<Multiplication, u32>::is_generator(&field, e)).count();
+        assert_eq!(generators, 10);
+
+        // the subgroup of order 11 is the quadratic residues
+        let h = CyclicGroup::<Multiplication, u32>::subgroup_generator(&field, &11).unwrap();
+        let mut squares = group.subgroup(&h).unwrap();
+        squares.sort();
+        assert_eq!(squares, vec![1, 2, 3, 4, 6, 8, 9, 12, 13, 16, 18]);
+
+        // zero and p are not units so generate nothing, while p + 5 reduces to the generator 5
+        for e in [0, 23] {
+            assert!(!CyclicGroup::<Multiplication, u32>::is_generator(&field, &e));
+            assert_eq!(CyclicGroup::<Multiplication, u32>::order_of(&field, &e), Err(Error::DivisionByZero));
+            assert_eq!(group.subgroup(&e), Err(Error::DivisionByZero));
+            assert!(!CyclicGroup::<Multiplication, u32>::is_in_subgroup(&field, &e, &11));
+        }
+        assert!(CyclicGroup::<Multiplication, u32>::is_generator(&field, &28));
+        assert_eq!(CyclicGroup::<Multiplication, u32>::order_of(&field, &28), Ok(22));
+
+        // a large field where p - 1 is smooth
+        let field = PrimeField::new((1u64 << 61) - 1);
+        let g = CyclicGroup::<Multiplication, u64>::generator(&field);
+        assert!(CyclicGroup::<Multiplication, u64>::is_generator(&field, &g));
+        assert_eq!(CyclicGroup::<Multiplication, u64>::order_of(&field, &g), Ok((1 << 61) - 2));
+        let h = CyclicGroup::<Multiplication, u64>::subgroup_generator(&field, &1321).unwrap();
+        assert_eq!(CyclicGroup::<Multiplication, u64>::order_of(&field, &h), Ok(1321));
+    }
+
+    #[test]
+    fn run_diffie_hellman_in_a_prime_order_subgroup() {
+        let rng = SystemRandom::new();
+        let p: u64 = random_safe_prime(&rng, 48).unwrap();
+        let q = (p - 1) / 2;
+        let field = PrimeField::new(p);
+
+        // the subgroup of prime order q has no small subgroups for an attacker to confine keys to
+        let g = CyclicGroup::<Multiplication, u64>::subgroup_generator(&field, &q).unwrap();
+        assert_eq!(CyclicGroup::<Multiplication, u64>::order_of(&field, &g), Ok(q));
+
+        let a = random_below(&rng, &q);
+        let b = random_below(&rng, &q);
+        let big_a = Monoid::<Multiplication, u64>::pow(&field, g, &a);
+        let big_b = Monoid::<Multiplication, u64>::pow(&field, g, &b);
+        assert!(CyclicGroup::<Multiplication, u64>::is_in_subgroup(&field, &big_a, &q));
+        assert!(CyclicGroup::<Multiplication, u64>::is_in_subgroup(&field, &big_b, &q));
+        assert_eq!(
+            Monoid::<Multiplication, u64>::pow(&field, big_b, &a),
+            Monoid::<Multiplication, u64>::pow(&field, big_a, &b)
+        );
+
+        // p - 1 has order two so it is rejected as a public value
+        assert!(!CyclicGroup::<Multiplication, u64>::is_in_subgroup(&field, &(p - 1), &q));
+    }
+
+}
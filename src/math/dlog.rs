@@ -0,0 +1,272 @@
+use crate::math::crt::crt;
+use crate::math::factor::factor;
+use crate::math::group::{Group, Monoid, Operation};
+use crate::math::integer::UnsignedInt;
+use crate::math::number_theory::isqrt;
+use crate::math::primes::random_below;
+use crate::math::Error;
+use ring::rand::SystemRandom;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// The number of random starting points Pollard's rho tries before giving up
+const RHO_ATTEMPTS: usize = 32;
+
+/// Returns g^e
+fn pow<T: Operation, E: Clone, S: Group<T, E>, X: UnsignedInt>(group: &S, g: &E, e: &X) -> E {
+    <S as Monoid<T, E>>::pow(group, g.clone(), e)
+}
+
+/// Returns an error unless h^order is the identity, as otherwise h cannot be a power of g
+fn check_order<T: Operation, E: Clone + PartialEq, S: Group<T, E>, X: UnsignedInt>(group: &S, h: &E, order: &X) -> Result<(), Error> {
+    if pow(group, h, order) == group.identity() {
+        Ok(())
+    } else {
+        Err(Error::NotInSubgroup)
+    }
+}
+
+/// Finds x with g^x = h where order is the order of g using Shanks' baby-step giant-step.
+///
+/// Writing x = i m + j with m = ceil(sqrt(order)) the baby steps g^j are stored and the giant
+/// steps h g^(-i m) are looked up among them, taking O(sqrt(order)) time and memory.
+pub fn baby_step_giant_step<T, E, S, X>(group: &S, g: &E, h: &E, order: &X) -> Result<X, Error>
+where
+    T: Operation,
+    E: Clone + Eq + Hash,
+    S: Group<T, E>,
+    X: UnsignedInt,
+{
+    check_order(group, h, order)?;
+
+    let root = isqrt(order);
+    let m = if root.clone() * root.clone() == *order { root } else { root + X::one() };
+
+    // baby steps g^j for j < m
+    let mut table = HashMap::new();
+    let mut power = group.identity();
+    let mut j = X::zero();
+    while j < m {
+        table.entry(power.clone()).or_insert(j.clone());
+        power = group.apply(power, g.clone());
+        j = j + X::one();
+    }
+
+    // giant steps h g^(-im) for i < m
+    let giant = group.inverse(pow(group, g, &m));
+    let mut gamma = h.clone();
+    let mut i = X::zero();
+    while i < m {
+        if let Some(j) = table.get(&gamma) {
+            return Ok((i * m + j.clone()) % order.clone());
+        }
+        gamma = group.apply(gamma, giant.clone());
+        i = i + X::one();
+    }
+    Err(Error::NotInSubgroup)
+}
+
+/// Finds x with g^x = h where the order of g is prime using Pollard's rho for logarithms.
+///
+/// The walk x -> x g, x^2 or x h, chosen by a hash of x, keeps track of x = g^a h^b. When Floyd's
+/// cycle finding meets g^a h^b = g^A h^B the logarithm is (a - A) / (B - b) mod the order. It
+/// takes O(sqrt(order)) time like baby-step giant-step but only constant memory.
+pub fn pollard_rho_log<T, E, S, X>(group: &S, g: &E, h: &E, order: &X) -> Result<X, Error>
+where
+    T: Operation,
+    E: Clone + Eq + Hash,
+    S: Group<T, E>,
+    X: UnsignedInt,
+{
+    check_order(group, h, order)?;
+    let n = order;
+
+    // each step updates the exponents (a, b) of g^a h^b alongside the element
+    let step = |(x, a, b): (E, X, X)| -> (E, X, X) {
+        let mut hasher = DefaultHasher::new();
+        x.hash(&mut hasher);
+        match hasher.finish() % 3 {
+            0 => (group.apply(x, g.clone()), a.add_mod(&X::one(), n), b),
+            1 => (group.apply(x.clone(), x), a.add_mod(&a, n), b.add_mod(&b, n)),
+            _ => (group.apply(x, h.clone()), a, b.add_mod(&X::one(), n)),
+        }
+    };
+
+    let rng = SystemRandom::new();
+    for _ in 0..RHO_ATTEMPTS {
+        let (a, b) = (random_below(&rng, n), random_below(&rng, n));
+        let start = group.apply(pow(group, g, &a), pow(group, h, &b));
+
+        let mut tortoise = step((start.clone(), a.clone(), b.clone()));
+        let mut hare = step(step((start, a, b)));
+        while tortoise.0 != hare.0 {
+            tortoise = step(tortoise);
+            hare = step(step(hare));
+        }
+
+        // g^a h^b = g^A h^B gives (B - b) x = a - A
+        let (_, a, b) = tortoise;
+        let (_, big_a, big_b) = hare;
+        let denominator = big_b.sub_mod(&b, n);
+        if let Some(inverse) = crate::math::euclid::mod_inverse(denominator, n.clone()) {
+            let x = a.sub_mod(&big_a, n).mul_mod(&inverse, n);
+            if pow(group, g, &x) == *h {
+                return Ok(x);
+            }
+        }
+    }
+    Err(Error::NotInSubgroup)
+}
+
+/// Finds x with g^x = h where order is the order of g using the Pohlig-Hellman algorithm.
+///
+/// The logarithm is found mod each prime power p^e dividing the order one base p digit at a
+/// time in the subgroup of order p, then the pieces are joined with the Chinese Remainder
+/// Theorem. The work depends on the largest prime factor of the order rather than the order
+/// itself, which is why groups of smooth order are insecure.
+pub fn pohlig_hellman<T, E, S, X>(group: &S, g: &E, h: &E, order: &X) -> Result<X, Error>
+where
+    T: Operation,
+    E: Clone + Eq + Hash,
+    S: Group<T, E>,
+    X: UnsignedInt,
+{
+    check_order(group, h, order)?;
+
+    let mut congruences = Vec::new();
+    for (p, e) in factor(order) {
+        let p_e = (0..e).fold(X::one(), |acc, _| acc * p.clone());
+        let cofactor = order.clone() / p_e.clone();
+
+        // g_i and h_i lie in the subgroup of order p^e and gamma generates its subgroup of order p
+        let g_i = pow(group, g, &cofactor);
+        let h_i = pow(group, h, &cofactor);
+        let gamma = pow(group, &g_i, &(p_e.clone() / p.clone()));
+
+        // the k-th digit is the log of (g_i^-x h_i)^(p^(e-1-k)) to the base gamma
+        let mut x = X::zero();
+        let mut p_k = X::one();
+        for k in 0..e {
+            let shift = (k + 1..e).fold(X::one(), |acc, _| acc * p.clone());
+            let remaining = group.apply(group.inverse(pow(group, &g_i, &x)), h_i.clone());
+            let digit = baby_step_giant_step(group, &gamma, &pow(group, &remaining, &shift), &p)?;
+            x = x + digit * p_k.clone();
+            p_k = p_k * p.clone();
+        }
+        congruences.push((x, p_e));
+    }
+
+    let (x, _) = crt(&congruences)?;
+    if pow(group, g, &x) == *h {
+        Ok(x)
+    } else {
+        Err(Error::NotInSubgroup)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::math::cyclic::CyclicGroup;
+    use crate::math::elliptic_curve::EllipticCurve;
+    use crate::math::field::PrimeField;
+    use crate::math::group::{Addition, Multiplication};
+    use crate::math::prime_gen::random_safe_prime;
+    use crate::math::units::UnitsModN;
+    use ring::rand::SystemRandom;
+
+    #[test]
+    fn run_baby_step_giant_step() {
+        let field = PrimeField::new(1_000_003u64);
+        let g = CyclicGroup::<Multiplication, u64>::generator(&field);
+        let order = 1_000_002u64;
+        for x in [0u64, 1, 2, 999, 123_456, order - 1] {
+            let h = Monoid::<Multiplication, u64>::pow(&field, g, &x);
+            assert_eq!(baby_step_giant_step::<Multiplication, _, _, _>(&field, &g, &h, &order), Ok(x));
+        }
+
+        // a perfect square order and the trivial group
+        let units = UnitsModN::new(17u32);
+        assert_eq!(baby_step_giant_step(&units, &3, &13, &16u32), Ok(4));
+        assert_eq!(baby_step_giant_step(&units, &1, &1, &1u32), Ok(0));
+
+        // 2 generates the squares mod 23 so 5 is not a power of it
+        let field = PrimeField::new(23u32);
+        assert_eq!(baby_step_giant_step::<Multiplication, _, _, _>(&field, &2, &5, &11u32), Err(Error::NotInSubgroup));
+    }
+
+    #[test]
+    fn run_pollard_rho_log() {
+        let rng = SystemRandom::new();
+        let p: u64 = random_safe_prime(&rng, 36).unwrap();
+        let q = p / 2;
+        let field = PrimeField::new(p);
+        let g = CyclicGroup::<Multiplication, u64>::subgroup_generator(&field, &q).unwrap();
+
+        for _ in 0..3 {
+            let x = random_below(&rng, &q);
+            let h = Monoid::<Multiplication, u64>::pow(&field, g, &x);
+            assert_eq!(pollard_rho_log::<Multiplication, _, _, _>(&field, &g, &h, &q), Ok(x));
+        }
+
+        // p - 1 has order two so it is outside the subgroup
+        assert_eq!(pollard_rho_log::<Multiplication, _, _, _>(&field, &g, &(p - 1), &q), Err(Error::NotInSubgroup));
+    }
+
+    #[test]
+    fn run_pohlig_hellman() {
+        // 2^61 - 1 is prime and p - 1 = 2 3^2 5^2 7 11 13 31 41 61 151 331 1321 is smooth, so a
+        // 61 bit logarithm falls apart into tiny ones
+        let p = (1u64 << 61) - 1;
+        let field = PrimeField::new(p);
+        let g = CyclicGroup::<Multiplication, u64>::generator(&field);
+        let x = 0x0123_4567_89ab_cdefu64;
+        let h = Monoid::<Multiplication, u64>::pow(&field, g, &x);
+        assert_eq!(pohlig_hellman::<Multiplication, _, _, _>(&field, &g, &h, &(p - 1)), Ok(x));
+
+        // the additive group mod n is always easy
+        let n = 1u64 << 40;
+        let ring = crate::math::ring::NaturalNumbersModN::new(n);
+        let x = 12345u64.mul_mod(&crate::math::euclid::mod_inverse(3, n).unwrap(), &n);
+        assert_eq!(pohlig_hellman::<Addition, _, _, _>(&ring, &3, &12345, &n), Ok(x));
+
+        // all three solvers agree
+        let field = PrimeField::new(10007u32);
+        let g = CyclicGroup::<Multiplication, u32>::generator(&field);
+        for x in [0u32, 5, 1000, 10005] {
+            let h = Monoid::<Multiplication, u32>::pow(&field, g, &x);
+            assert_eq!(pohlig_hellman::<Multiplication, _, _, _>(&field, &g, &h, &10006), Ok(x));
+            assert_eq!(baby_step_giant_step::<Multiplication, _, _, _>(&field, &g, &h, &10006), Ok(x));
+        }
+        assert_eq!(pohlig_hellman::<Multiplication, _, _, _>(&field, &10006, &5, &2u32), Err(Error::NotInSubgroup));
+    }
+
+    #[test]
+    fn run_elliptic_curve_logarithms() {
+        // y^2 = x^3 + 2x + 3 mod 1009 has 1068 = 2^2 3 89 points including infinity and is cyclic
+        let curve = EllipticCurve::new(PrimeField::new(1009u64), 2, 3);
+        let points: Vec<_> = (0..1009).flat_map(|x| (0..1009).map(move |y| (x, y))).filter_map(|(x, y)| curve.point(x, y).ok()).collect();
+        assert_eq!(points.len() + 1, 1068);
+
+        // find a point which generates the whole group
+        let generator = points
+            .iter()
+            .find(|&g| [2u64, 3, 89].iter().all(|&q| !curve.multiply(g, &(1068 / q)).is_infinity()))
+            .unwrap()
+            .clone();
+
+        for x in [1u64, 100, 1067] {
+            let h = curve.multiply(&generator, &x);
+            assert_eq!(baby_step_giant_step(&curve, &generator, &h, &1068u64), Ok(x));
+            assert_eq!(pohlig_hellman(&curve, &generator, &h, &1068u64), Ok(x));
+        }
+
+        // rho needs a subgroup of prime order, here the one of order 89
+        let g89 = curve.multiply(&generator, &12u64);
+        let h = curve.multiply(&g89, &50u64);
+        assert_eq!(pollard_rho_log(&curve, &g89, &h, &89u64), Ok(50));
+        assert_eq!(pollard_rho_log(&curve, &g89, &generator, &89u64), Err(Error::NotInSubgroup));
+    }
+
+}
@@ -0,0 +1,316 @@
+use crate::math::euclid::gcd;
+use crate::math::factor::primes_in_range;
+use crate::math::integer::UnsignedInt;
+use crate::math::primes::random_below;
+use ring::rand::SecureRandom;
+
+/// The number of curves and the smoothness bounds used by Lenstra's elliptic curve method
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EcmParams {
+    /// The number of random curves tried before giving up
+    pub curves: usize,
+    /// Stage 1 finds p when every prime power dividing the order of the curve mod p is at most b1
+    pub b1: u32,
+    /// Stage 2 also finds p when the order has a single prime factor above b1 which is at most b2
+    pub b2: u32,
+}
+
+impl Default for EcmParams {
+    /// Bounds suited to factors of around 20 decimal digits
+    fn default() -> Self {
+        EcmParams { curves: 100, b1: 2000, b2: 150_000 }
+    }
+}
+
+/// The stage of the elliptic curve method which revealed a factor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    One,
+    Two,
+}
+
+/// A curve y^2 = x^3 + ax + b mod N along with the starting point used on it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Curve<E> {
+    pub a: E,
+    pub b: E,
+    pub x: E,
+    pub y: E,
+}
+
+/// A non-trivial factor of N along with the curve and stage which found it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EcmFactor<E> {
+    pub factor: E,
+    pub curve: Curve<E>,
+    pub stage: Stage,
+    /// The number of curves tried including the successful one
+    pub curves_tried: usize,
+}
+
+/// Searches for a factor of n using Lenstra's elliptic curve method.
+///
+/// The points of a random curve mod n form a group mod each prime factor p whose order is a
+/// random number near p. When that order is smooth, multiplying a point by every small prime
+/// power reaches the identity mod p but not mod n which reveals p through a gcd. Unlike Pollard's
+/// p-1 method every new curve is another chance of a smooth order.
+///
+/// Returns None if no curve found a factor, and without trying any if n is even or below 5,
+/// which should be split by trial division instead.
+pub fn ecm<E: UnsignedInt>(n: &E, params: &EcmParams, rng: &dyn SecureRandom) -> Option<EcmFactor<E>> {
+    if n.is_even() || *n < E::from_u32(5) {
+        return None;
+    }
+
+    let stage_1_primes = primes_in_range(2, params.b1 as u64 + 1);
+    let stage_2_primes = primes_in_range(params.b1 as u64 + 1, params.b2 as u64 + 1);
+
+    for curves_tried in 1..=params.curves {
+        // choose the point first and then b so the point lies on the curve
+        let (x, y, a) = (random_below(rng, n), random_below(rng, n), random_below(rng, n));
+        let b = y.mul_mod(&y, n).sub_mod(&x.mul_mod(&x, n).mul_mod(&x, n), n).sub_mod(&a.mul_mod(&x, n), n);
+        let curve = Curve { a, b, x, y };
+        let found = |factor: E, stage| Some(EcmFactor { factor, curve: curve.clone(), stage, curves_tried });
+
+        // a curve singular mod p has discriminant 4a^3 + 27b^2 = 0 mod p
+        let (a, b) = (&curve.a, &curve.b);
+        let discriminant = E::from_u32(4)
+            .mul_mod(&a.mul_mod(a, n).mul_mod(a, n), n)
+            .add_mod(&E::from_u32(27).mul_mod(&b.mul_mod(b, n), n), n);
+        let g = gcd(discriminant, n.clone());
+        if !g.is_one() {
+            if g != *n {
+                return found(g, Stage::One);
+            }
+            continue;
+        }
+
+        let arithmetic = ProjectiveCurve { n: n.clone(), a: curve.a.clone() };
+        let mut point = Point { x: curve.x.clone(), y: curve.y.clone(), z: E::one() };
+
+        // stage 1 multiplies by the largest power of each prime not above b1
+        for &q in &stage_1_primes {
+            let mut power = q;
+            while power <= params.b1 as u64 / q {
+                power *= q;
+            }
+            point = arithmetic.multiply(&point, power);
+        }
+        let g = gcd(point.z.clone(), n.clone());
+        if g == *n {
+            continue;
+        }
+        if !g.is_one() {
+            return found(g, Stage::One);
+        }
+
+        if let Some(g) = arithmetic.stage_2(&point, &stage_2_primes) {
+            return found(g, Stage::Two);
+        }
+    }
+    None
+}
+
+/// A point in homogeneous projective coordinates (X : Y : Z) standing for (X/Z, Y/Z), so no
+/// inversions are needed. The identity is any point with Z = 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Point<E> {
+    x: E,
+    y: E,
+    z: E,
+}
+
+/// The group law of y^2 z = x^3 + a x z^2 + b z^3 mod n, which does not depend on b.
+///
+/// Mod n this is not a group but the formulas agree with the group law mod each prime factor so
+/// a point which is the identity mod p has Z = 0 mod p.
+struct ProjectiveCurve<E> {
+    n: E,
+    a: E,
+}
+
+impl<E: UnsignedInt> ProjectiveCurve<E> {
+    fn add(&self, p1: &Point<E>, p2: &Point<E>) -> Point<E> {
+        let n = &self.n;
+        if p1.z.is_zero() {
+            return p2.clone();
+        }
+        if p2.z.is_zero() {
+            return p1.clone();
+        }
+
+        let y2z1 = p2.y.mul_mod(&p1.z, n);
+        let y1z2 = p1.y.mul_mod(&p2.z, n);
+        let x2z1 = p2.x.mul_mod(&p1.z, n);
+        let x1z2 = p1.x.mul_mod(&p2.z, n);
+        let u = y2z1.sub_mod(&y1z2, n);
+        let v = x2z1.sub_mod(&x1z2, n);
+        if v.is_zero() {
+            return if u.is_zero() { self.double(p1) } else { Point { x: E::zero(), y: E::one(), z: E::zero() } };
+        }
+
+        // u = Y2 Z1 - Y1 Z2, v = X2 Z1 - X1 Z2, w = u^2 Z1 Z2 - v^3 - 2 v^2 X1 Z2
+        let z1z2 = p1.z.mul_mod(&p2.z, n);
+        let vv = v.mul_mod(&v, n);
+        let vvv = vv.mul_mod(&v, n);
+        let vv_x1z2 = vv.mul_mod(&x1z2, n);
+        let w = u.mul_mod(&u, n).mul_mod(&z1z2, n).sub_mod(&vvv, n).sub_mod(&vv_x1z2.add_mod(&vv_x1z2, n), n);
+
+        Point {
+            x: v.mul_mod(&w, n),
+            y: u.mul_mod(&vv_x1z2.sub_mod(&w, n), n).sub_mod(&vvv.mul_mod(&y1z2, n), n),
+            z: vvv.mul_mod(&z1z2, n),
+        }
+    }
+
+    fn double(&self, p: &Point<E>) -> Point<E> {
+        let n = &self.n;
+        if p.z.is_zero() || p.y.is_zero() {
+            return Point { x: E::zero(), y: E::one(), z: E::zero() };
+        }
+
+        // w = a Z^2 + 3 X^2, s = Y Z, B = X Y s, h = w^2 - 8B
+        let small = |k: u32| E::from_u32(k);
+        let w = self.a.mul_mod(&p.z.mul_mod(&p.z, n), n).add_mod(&small(3).mul_mod(&p.x.mul_mod(&p.x, n), n), n);
+        let s = p.y.mul_mod(&p.z, n);
+        let b = p.x.mul_mod(&p.y, n).mul_mod(&s, n);
+        let h = w.mul_mod(&w, n).sub_mod(&small(8).mul_mod(&b, n), n);
+        let ss = s.mul_mod(&s, n);
+
+        Point {
+            x: small(2).mul_mod(&h, n).mul_mod(&s, n),
+            y: w
+                .mul_mod(&small(4).mul_mod(&b, n).sub_mod(&h, n), n)
+                .sub_mod(&small(8).mul_mod(&p.y.mul_mod(&p.y, n), n).mul_mod(&ss, n), n),
+            z: small(8).mul_mod(&ss, n).mul_mod(&s, n),
+        }
+    }
+
+    /// Returns k p using double and add
+    fn multiply(&self, p: &Point<E>, k: u64) -> Point<E> {
+        let mut result = Point { x: E::zero(), y: E::one(), z: E::zero() };
+        for i in (0..u64::BITS - k.leading_zeros()).rev() {
+            result = self.double(&result);
+            if (k >> i) & 1 == 1 {
+                result = self.add(&result, p);
+            }
+        }
+        result
+    }
+
+    /// Walks q p over the primes q in stage 2 multiplying the Z coordinates together, so when the
+    /// order of p mod some prime factor is one of those primes the product shares that factor with n.
+    ///
+    /// Consecutive primes differ by small even gaps so each step adds a precomputed multiple of p.
+    fn stage_2(&self, p: &Point<E>, primes: &[u64]) -> Option<E> {
+        let n = &self.n;
+        let first = *primes.first()?;
+
+        let max_gap = primes.windows(2).map(|w| w[1] - w[0]).max().unwrap_or(0);
+        let doubled = self.double(p);
+        let mut gaps = vec![doubled.clone()];
+        while (gaps.len() as u64) * 2 < max_gap {
+            gaps.push(self.add(gaps.last().unwrap(), &doubled));
+        }
+
+        let mut q_p = self.multiply(p, first);
+        let mut product = q_p.z.clone();
+        for w in primes.windows(2) {
+            q_p = self.add(&q_p, &gaps[((w[1] - w[0]) / 2 - 1) as usize]);
+            product = product.mul_mod(&q_p.z, n);
+        }
+
+        let g = gcd(product, n.clone());
+        (!g.is_one() && g != *n).then_some(g)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::math::BigUint;
+    use ring::rand::SystemRandom;
+
+    /// The number of runs allowed before a test gives up, so a failure means a bug rather than
+    /// an unlucky choice of curves
+    const ATTEMPTS: usize = 20;
+
+    fn ecm_until_found<E: UnsignedInt>(n: &E, params: &EcmParams, rng: &dyn SecureRandom) -> EcmFactor<E> {
+        (0..ATTEMPTS).find_map(|_| ecm(n, params, rng)).expect("no factor found in any run")
+    }
+
+    #[test]
+    fn run_curve_arithmetic() {
+        // count the points on y^2 = x^3 + 2x + 3 mod 1009 by brute force
+        let (p, a, b) = (1009u64, 2u64, 3u64);
+        let on_curve = |x: u64, y: u64| (y * y) % p == (x * x % p * x + a * x + b) % p;
+        let points: Vec<(u64, u64)> = (0..p).flat_map(|x| (0..p).map(move |y| (x, y))).filter(|&(x, y)| on_curve(x, y)).collect();
+        let order = points.len() as u64 + 1;
+
+        let curve = ProjectiveCurve { n: p, a };
+        for &(x, y) in points.iter().take(50) {
+            let point = Point { x, y, z: 1 };
+
+            // the order of the group takes every point to the identity
+            assert_eq!(curve.multiply(&point, order).z, 0);
+
+            // and affine coordinates agree however a multiple is reached
+            let affine = |q: &Point<u64>| {
+                let inverse = crate::math::euclid::mod_inverse(q.z, p).unwrap();
+                (q.x * inverse % p, q.y * inverse % p)
+            };
+            let five = curve.multiply(&point, 5);
+            let sum = curve.add(&curve.double(&curve.double(&point)), &point);
+            assert_eq!(affine(&five), affine(&sum));
+            assert!(on_curve(affine(&five).0, affine(&five).1));
+        }
+    }
+
+    #[test]
+    fn run_ecm() {
+        let rng = SystemRandom::new();
+        let params = EcmParams { curves: 200, b1: 500, b2: 20_000 };
+
+        // two 31 bit primes
+        let (p, q) = (2_147_483_647u64, 2_147_483_629u64);
+        let result = ecm_until_found(&(p * q), &params, &rng);
+        assert!(result.factor == p || result.factor == q);
+        assert!(result.curves_tried <= params.curves);
+
+        // a 24 bit factor of a 151 bit number
+        let p = BigUint::from(16_777_213u32);
+        let q = BigUint::from_hex("7fffffffffffffffffffffffffffffff").unwrap();
+        let n = &p * &q;
+        let result = ecm_until_found(&n, &params, &rng);
+        assert!(result.factor == p || result.factor == q);
+        let curve = &result.curve;
+        let lhs = &curve.y * &curve.y % &n;
+        let rhs = (&curve.x * &curve.x * &curve.x + &curve.a * &curve.x + &curve.b) % &n;
+        assert_eq!(lhs, rhs);
+    }
+
+    #[test]
+    fn run_ecm_reports_stages() {
+        let rng = SystemRandom::new();
+
+        // with a tiny stage 1 most factors come from stage 2
+        let params = EcmParams { curves: 500, b1: 20, b2: 50_000 };
+        let n = 1_000_003u64 * 1_000_033;
+        let found_in_stage_two = (0..ATTEMPTS).any(|_| {
+            let result = ecm_until_found(&n, &params, &rng);
+            assert_eq!(n % result.factor, 0);
+            result.stage == Stage::Two
+        });
+        assert!(found_in_stage_two);
+
+        // nothing is found when there are no curves to try
+        assert_eq!(ecm(&n, &EcmParams { curves: 0, ..params }, &rng), None);
+
+        // even and tiny numbers are left to trial division
+        for n in [0u64, 1, 3, 4, 1_000_003 * 1_000_033 * 2] {
+            assert_eq!(ecm(&n, &params, &rng), None);
+        }
+    }
+
+}
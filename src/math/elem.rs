@@ -0,0 +1,246 @@
+use crate::math::group::{Addition, Group, Monoid, Multiplication};
+use crate::math::integer::UnsignedInt;
+use crate::math::Error;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// An element paired with a reference to the algebraic structure it belongs to.
+///
+/// The operators delegate to the Monoid and Group impls of the structure so any Group, Ring or
+/// Field can be used with ordinary arithmetic syntax:
+/// - `+` applies the additive monoid
+/// - `-` and negation use the additive group inverse
+/// - `*` applies the multiplicative monoid
+/// - `/` uses the multiplicative group inverse
+///
+/// Both operands must belong to equal structures, such as two PrimeFields with the same modulus,
+/// and the operators panic otherwise.
+pub struct Elem<'a, S, E> {
+    structure: &'a S,
+    value: E,
+}
+
+impl<'a, S, E> Elem<'a, S, E> {
+    pub fn new(structure: &'a S, value: E) -> Self {
+        Elem { structure, value }
+    }
+
+    /// Returns the structure the element belongs to
+    pub fn structure(&self) -> &'a S {
+        self.structure
+    }
+
+    pub fn value(&self) -> &E {
+        &self.value
+    }
+
+    pub fn into_value(self) -> E {
+        self.value
+    }
+
+    /// Wraps a value in the same structure as this element
+    fn with(&self, value: E) -> Self {
+        Elem::new(self.structure, value)
+    }
+
+    /// Raises the element to the power of exponent under multiplication
+    pub fn pow<X: UnsignedInt>(self, exponent: &X) -> Self
+    where
+        S: Monoid<Multiplication, E>,
+        E: Clone,
+    {
+        let structure = self.structure;
+        Elem::new(structure, <S as Monoid<Multiplication, E>>::pow(structure, self.value, exponent))
+    }
+
+    /// Divides returning an error rather than panicking when rhs has no inverse or belongs to a
+    /// different structure
+    pub fn checked_div(self, rhs: Self) -> Result<Self, Error>
+    where
+        S: Group<Multiplication, E> + PartialEq,
+    {
+        if !self.same_structure(&rhs) {
+            return Err(Error::DifferentStructures);
+        }
+        let structure = self.structure;
+        let inverse = <S as Group<Multiplication, E>>::checked_inverse(structure, rhs.value)?;
+        Ok(Elem::new(structure, <S as Monoid<Multiplication, E>>::apply(structure, self.value, inverse)))
+    }
+
+    /// Returns true if both elements belong to the same structure or to equal ones
+    fn same_structure(&self, other: &Self) -> bool
+    where
+        S: PartialEq,
+    {
+        std::ptr::eq(self.structure, other.structure) || self.structure == other.structure
+    }
+
+    fn check_same_structure(&self, other: &Self)
+    where
+        S: PartialEq,
+    {
+        assert!(self.same_structure(other), "{}", Error::DifferentStructures);
+    }
+}
+
+impl<S, E: Clone> Clone for Elem<'_, S, E> {
+    fn clone(&self) -> Self {
+        self.with(self.value.clone())
+    }
+}
+
+impl<S, E: Copy> Copy for Elem<'_, S, E> {}
+
+/// Elements are equal when their values are equal and they belong to equal structures
+impl<S: PartialEq, E: PartialEq> PartialEq for Elem<'_, S, E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.same_structure(other) && self.value == other.value
+    }
+}
+
+impl<S: Eq, E: Eq> Eq for Elem<'_, S, E> {}
+
+impl<S, E: fmt::Display> fmt::Display for Elem<'_, S, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<S, E: fmt::Debug> fmt::Debug for Elem<'_, S, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<'a, S: Monoid<Addition, E> + PartialEq, E> Add for Elem<'a, S, E> {
+    type Output = Elem<'a, S, E>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.check_same_structure(&rhs);
+        let structure = self.structure;
+        Elem::new(structure, <S as Monoid<Addition, E>>::apply(structure, self.value, rhs.value))
+    }
+}
+
+impl<'a, S: Group<Addition, E> + PartialEq, E> Sub for Elem<'a, S, E> {
+    type Output = Elem<'a, S, E>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.check_same_structure(&rhs);
+        let structure = self.structure;
+        let inverse = <S as Group<Addition, E>>::inverse(structure, rhs.value);
+        Elem::new(structure, <S as Monoid<Addition, E>>::apply(structure, self.value, inverse))
+    }
+}
+
+impl<'a, S: Group<Addition, E>, E> Neg for Elem<'a, S, E> {
+    type Output = Elem<'a, S, E>;
+
+    fn neg(self) -> Self::Output {
+        let structure = self.structure;
+        Elem::new(structure, <S as Group<Addition, E>>::inverse(structure, self.value))
+    }
+}
+
+impl<'a, S: Monoid<Multiplication, E> + PartialEq, E> Mul for Elem<'a, S, E> {
+    type Output = Elem<'a, S, E>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.check_same_structure(&rhs);
+        let structure = self.structure;
+        Elem::new(structure, <S as Monoid<Multiplication, E>>::apply(structure, self.value, rhs.value))
+    }
+}
+
+impl<'a, S: Group<Multiplication, E> + PartialEq, E> Div for Elem<'a, S, E> {
+    type Output = Elem<'a, S, E>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.check_same_structure(&rhs);
+        let structure = self.structure;
+        let inverse = <S as Group<Multiplication, E>>::inverse(structure, rhs.value);
+        Elem::new(structure, <S as Monoid<Multiplication, E>>::apply(structure, self.value, inverse))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::math::field::PrimeField;
+    use crate::math::group::AdditiveIntegers;
+    use crate::math::ring::NaturalNumbersModN;
+    use crate::math::BigUint;
+
+    #[test]
+    fn run_prime_field_elements() {
+        let field = PrimeField::new(11u32);
+        let x = |v| Elem::new(&field, v);
+
+        assert_eq!(x(3) + x(10), x(2));
+        assert_eq!(x(1) - x(3), x(9));
+        assert_eq!(x(2) * x(10), x(9));
+        assert_eq!(x(10) / x(2), x(5));
+        assert_eq!(-x(4), x(7));
+        assert_eq!((x(3) + x(4)) * x(5) / x(2) - x(10), x(2));
+        assert_eq!(format!("{}", x(7)), "7");
+        assert_eq!(*x(7).value(), 7);
+        assert_eq!(x(10).checked_div(x(2)), Ok(x(5)));
+        assert_eq!(x(10).checked_div(x(0)), Err(Error::DivisionByZero));
+        assert_eq!(x(2).pow(&10u32), x(1));
+    }
+
+    #[test]
+    fn run_ring_elements() {
+        let ring = NaturalNumbersModN::new(12u64);
+        let x = |v| Elem::new(&ring, v);
+
+        assert_eq!(x(7) + x(8), x(3));
+        assert_eq!(x(2) - x(5), x(9));
+        assert_eq!(x(3) * x(4), x(0));
+        assert_eq!(-x(0), x(0));
+    }
+
+    #[test]
+    fn run_group_elements() {
+        let group = AdditiveIntegers();
+        let a = Elem::new(&group, 10);
+        let b = Elem::new(&group, 25);
+
+        assert_eq!(a + b, Elem::new(&group, 35));
+        assert_eq!(a - b, Elem::new(&group, -15));
+        assert_eq!(-a, Elem::new(&group, -10));
+    }
+
+    #[test]
+    fn run_big_field_elements() {
+        let p = BigUint::from_hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff").unwrap();
+        let field = PrimeField::new(p.clone());
+        let a = Elem::new(&field, &p - BigUint::one());
+        let b = Elem::new(&field, BigUint::from(2u32));
+
+        assert_eq!(a.clone() + b.clone(), Elem::new(&field, BigUint::one()));
+        assert_eq!(a.clone() * a.clone(), Elem::new(&field, BigUint::one()));
+        assert_eq!((a.clone() / b.clone()) * b, a);
+    }
+
+    #[test]
+    fn run_elements_of_different_structures() {
+        // equal structures at different addresses can be mixed
+        let (f11, g11, f13) = (PrimeField::new(11u32), PrimeField::new(11u32), PrimeField::new(13u32));
+        assert_eq!(Elem::new(&f11, 3) + Elem::new(&g11, 10), Elem::new(&f11, 2));
+        assert_eq!(Elem::new(&f11, 3), Elem::new(&g11, 3));
+
+        // the same value in different fields is a different element
+        assert_ne!(Elem::new(&f11, 3), Elem::new(&f13, 3));
+        assert_eq!(Elem::new(&f11, 3).checked_div(Elem::new(&f13, 2)), Err(Error::DifferentStructures));
+    }
+
+    #[test]
+    #[should_panic(expected = "elements belong to different structures")]
+    fn run_adding_elements_of_different_structures() {
+        let (f11, f13) = (PrimeField::new(11u32), PrimeField::new(13u32));
+        let _ = Elem::new(&f11, 3) + Elem::new(&f13, 3);
+    }
+
+}
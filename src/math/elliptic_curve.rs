@@ -0,0 +1,422 @@
+use crate::math::field::Field;
+use crate::math::group::{AbelianGroup, Addition, Group, Monoid, Multiplication, ScalarMul};
+use crate::math::integer::UnsignedInt;
+use crate::math::Error;
+
+/// A point on an elliptic curve in affine coordinates, or the point at infinity which is the
+/// identity of the group
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Point<E> {
+    Infinity,
+    Affine { x: E, y: E },
+}
+
+impl<E> Point<E> {
+    pub fn is_infinity(&self) -> bool {
+        matches!(self, Point::Infinity)
+    }
+
+    /// Returns the x coordinate or None for the point at infinity
+    pub fn x(&self) -> Option<&E> {
+        match self {
+            Point::Infinity => None,
+            Point::Affine { x, .. } => Some(x),
+        }
+    }
+
+    /// Returns the y coordinate or None for the point at infinity
+    pub fn y(&self) -> Option<&E> {
+        match self {
+            Point::Infinity => None,
+            Point::Affine { y, .. } => Some(y),
+        }
+    }
+}
+
+/// A point in Jacobian coordinates (X, Y, Z) standing for the affine point (X/Z^2, Y/Z^3).
+///
+/// Addition and doubling need no inversions in this form so a scalar multiplication inverts
+/// once, on converting back to affine coordinates. A point has many representations so they
+/// are compared after converting with EllipticCurve::to_affine. Z is zero at infinity.
+#[derive(Debug, Clone)]
+pub struct JacobianPoint<E> {
+    pub x: E,
+    pub y: E,
+    pub z: E,
+}
+
+/// The curve y^2 = x^3 + ax + b in short Weierstrass form over a field of characteristic other
+/// than two or three.
+///
+/// Its points together with the point at infinity form an abelian group under the chord and
+/// tangent rule, in either affine or Jacobian coordinates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EllipticCurve<F, E> {
+    field: F,
+    a: E,
+    b: E,
+}
+
+impl<F: Field<E>, E: Clone + PartialEq> EllipticCurve<F, E> {
+    pub fn new(field: F, a: E, b: E) -> Self {
+        match EllipticCurve::try_new(field, a, b) {
+            Ok(curve) => curve,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Returns an error rather than panicking if the field has characteristic 2 or 3, where every
+    /// curve in this form is singular, or if the discriminant 4a^3 + 27b^2 is zero, when the
+    /// curve has a cusp or a node. The coefficients are reduced into the field.
+    pub fn try_new(field: F, a: E, b: E) -> Result<Self, Error> {
+        let mut curve = EllipticCurve { field, a, b };
+        curve.a = curve.reduce(curve.a.clone());
+        curve.b = curve.reduce(curve.b.clone());
+        if curve.constant(2) == curve.zero() || curve.constant(3) == curve.zero() {
+            return Err(Error::UnsupportedCharacteristic);
+        }
+
+        let f = &curve.field;
+        let a_cubed = f.multiply(f.multiply(curve.a.clone(), curve.a.clone()), curve.a.clone());
+        let b_squared = f.multiply(curve.b.clone(), curve.b.clone());
+        let discriminant = f.add(f.multiply(curve.constant(4), a_cubed), f.multiply(curve.constant(27), b_squared));
+        if discriminant == curve.zero() {
+            return Err(Error::SingularCurve);
+        }
+
+        Ok(curve)
+    }
+
+    pub fn field(&self) -> &F {
+        &self.field
+    }
+
+    pub fn a(&self) -> &E {
+        &self.a
+    }
+
+    pub fn b(&self) -> &E {
+        &self.b
+    }
+
+    /// Returns the point (x, y) or NotOnCurve if it does not satisfy the curve equation or its
+    /// coordinates are not reduced elements of the field
+    pub fn point(&self, x: E, y: E) -> Result<Point<E>, Error> {
+        let point = Point::Affine { x, y };
+        if !self.is_on_curve(&point) {
+            return Err(Error::NotOnCurve);
+        }
+        Ok(point)
+    }
+
+    /// Returns true for the point at infinity and affine points with reduced coordinates and
+    /// y^2 = x^3 + ax + b. The group law compares coordinates directly so (p, y) is rejected
+    /// even though it satisfies the equation whenever (0, y) does.
+    pub fn is_on_curve(&self, point: &Point<E>) -> bool {
+        match point {
+            Point::Infinity => true,
+            Point::Affine { x, y } => {
+                *x == self.reduce(x.clone())
+                    && *y == self.reduce(y.clone())
+                    && self.field.multiply(y.clone(), y.clone()) == self.rhs(x)
+            }
+        }
+    }
+
+    /// Returns x^3 + ax + b
+    pub fn rhs(&self, x: &E) -> E {
+        let f = &self.field;
+        let x_squared = f.multiply(x.clone(), x.clone());
+        f.add(f.multiply(f.add(x_squared, self.a.clone()), x.clone()), self.b.clone())
+    }
+
+    pub fn to_jacobian(&self, point: &Point<E>) -> JacobianPoint<E> {
+        match point {
+            Point::Infinity => Monoid::<Addition, JacobianPoint<E>>::identity(self),
+            Point::Affine { x, y } => JacobianPoint { x: x.clone(), y: y.clone(), z: self.one() },
+        }
+    }
+
+    /// Returns the affine point (X/Z^2, Y/Z^3) using a single inversion
+    pub fn to_affine(&self, point: &JacobianPoint<E>) -> Point<E> {
+        let f = &self.field;
+        if point.z == self.zero() {
+            return Point::Infinity;
+        }
+        let z_inverse = f.divide(self.one(), point.z.clone());
+        let z_inverse_squared = f.multiply(z_inverse.clone(), z_inverse.clone());
+        let x = f.multiply(point.x.clone(), z_inverse_squared.clone());
+        let y = f.multiply(point.y.clone(), f.multiply(z_inverse_squared, z_inverse));
+        Point::Affine { x, y }
+    }
+
+    /// Returns n.P computed in Jacobian coordinates, which is quicker than the affine double and
+    /// add of ScalarMul as it inverts only once
+    pub fn multiply<X: UnsignedInt>(&self, point: &Point<E>, n: &X) -> Point<E> {
+        self.to_affine(&self.scalar_mul(self.to_jacobian(point), n))
+    }
+
+    fn zero(&self) -> E {
+        <F as Monoid<Addition, E>>::identity(&self.field)
+    }
+
+    fn one(&self) -> E {
+        <F as Monoid<Multiplication, E>>::identity(&self.field)
+    }
+
+    /// Returns the integer n as a field element, only used when the curve is created as the group
+    /// law doubles with additions instead
+    fn constant(&self, n: u32) -> E {
+        self.field.scalar_mul(self.one(), &n)
+    }
+
+    /// Returns e as a reduced element of the field by adding zero
+    fn reduce(&self, e: E) -> E {
+        self.field.add(e, self.zero())
+    }
+
+    /// Returns 2e with a single addition
+    fn twice(&self, e: E) -> E {
+        self.field.add(e.clone(), e)
+    }
+
+    /// Returns 2P in Jacobian coordinates:
+    /// S = 4XY^2, M = 3X^2 + aZ^4, X' = M^2 - 2S, Y' = M(S - X') - 8Y^4, Z' = 2YZ
+    fn double(&self, p: &JacobianPoint<E>) -> JacobianPoint<E> {
+        let f = &self.field;
+        if p.z == self.zero() || p.y == self.zero() {
+            return Monoid::<Addition, JacobianPoint<E>>::identity(self);
+        }
+        let y_squared = f.multiply(p.y.clone(), p.y.clone());
+        let s = self.twice(self.twice(f.multiply(p.x.clone(), y_squared.clone())));
+        let z_squared = f.multiply(p.z.clone(), p.z.clone());
+        let a_z4 = f.multiply(self.a.clone(), f.multiply(z_squared.clone(), z_squared));
+        let x_squared = f.multiply(p.x.clone(), p.x.clone());
+        let m = f.add(f.add(self.twice(x_squared.clone()), x_squared), a_z4);
+
+        let x = f.subtract(f.multiply(m.clone(), m.clone()), self.twice(s.clone()));
+        let y_fourth = f.multiply(y_squared.clone(), y_squared);
+        let y = f.subtract(f.multiply(m, f.subtract(s, x.clone())), self.twice(self.twice(self.twice(y_fourth))));
+        let z = f.multiply(self.twice(p.y.clone()), p.z.clone());
+        JacobianPoint { x, y, z }
+    }
+}
+
+impl<F: Field<E>, E: Clone + PartialEq> Monoid<Addition, Point<E>> for EllipticCurve<F, E> {
+    /// The line through P and Q meets the curve in a third point whose reflection is P + Q, with
+    /// the tangent at P taking the place of the line when P = Q
+    fn apply(&self, e1: Point<E>, e2: Point<E>) -> Point<E> {
+        let f = &self.field;
+        let ((x1, y1), (x2, y2)) = match (e1, e2) {
+            (Point::Infinity, e) | (e, Point::Infinity) => return e,
+            (Point::Affine { x: x1, y: y1 }, Point::Affine { x: x2, y: y2 }) => ((x1, y1), (x2, y2)),
+        };
+
+        // P + (-P) has a vertical line which meets the curve again only at infinity
+        if x1 == x2 && f.add(y1.clone(), y2.clone()) == self.zero() {
+            return Point::Infinity;
+        }
+        let slope = if x1 == x2 {
+            let x1_squared = f.multiply(x1.clone(), x1.clone());
+            let numerator = f.add(f.add(self.twice(x1_squared.clone()), x1_squared), self.a.clone());
+            f.divide(numerator, self.twice(y1.clone()))
+        } else {
+            f.divide(f.subtract(y2, y1.clone()), f.subtract(x2.clone(), x1.clone()))
+        };
+        let x3 = f.subtract(f.subtract(f.multiply(slope.clone(), slope.clone()), x1.clone()), x2);
+        let y3 = f.subtract(f.multiply(slope, f.subtract(x1, x3.clone())), y1);
+        Point::Affine { x: x3, y: y3 }
+    }
+
+    fn identity(&self) -> Point<E> {
+        Point::Infinity
+    }
+}
+
+impl<F: Field<E>, E: Clone + PartialEq> Group<Addition, Point<E>> for EllipticCurve<F, E> {
+    /// Reflects the point in the x axis
+    fn inverse(&self, e: Point<E>) -> Point<E> {
+        match e {
+            Point::Infinity => Point::Infinity,
+            Point::Affine { x, y } => Point::Affine { x, y: <F as Group<Addition, E>>::inverse(&self.field, y) },
+        }
+    }
+}
+
+impl<F: Field<E>, E: Clone + PartialEq> AbelianGroup<Addition, Point<E>> for EllipticCurve<F, E> {}
+
+impl<F: Field<E>, E: Clone + PartialEq> Monoid<Addition, JacobianPoint<E>> for EllipticCurve<F, E> {
+    /// U1 = X1 Z2^2, U2 = X2 Z1^2, S1 = Y1 Z2^3, S2 = Y2 Z1^3, H = U2 - U1, R = S2 - S1
+    /// X3 = R^2 - H^3 - 2 U1 H^2, Y3 = R(U1 H^2 - X3) - S1 H^3, Z3 = H Z1 Z2
+    fn apply(&self, e1: JacobianPoint<E>, e2: JacobianPoint<E>) -> JacobianPoint<E> {
+        let f = &self.field;
+        if e1.z == self.zero() {
+            return e2;
+        }
+        if e2.z == self.zero() {
+            return e1;
+        }
+        let z1_squared = f.multiply(e1.z.clone(), e1.z.clone());
+        let z2_squared = f.multiply(e2.z.clone(), e2.z.clone());
+        let u1 = f.multiply(e1.x.clone(), z2_squared.clone());
+        let u2 = f.multiply(e2.x.clone(), z1_squared.clone());
+        let s1 = f.multiply(e1.y.clone(), f.multiply(z2_squared, e2.z.clone()));
+        let s2 = f.multiply(e2.y.clone(), f.multiply(z1_squared, e1.z.clone()));
+
+        // the same x coordinate means the points are equal or inverses
+        if u1 == u2 {
+            if s1 == s2 {
+                return self.double(&e1);
+            }
+            return Monoid::<Addition, JacobianPoint<E>>::identity(self);
+        }
+
+        let h = f.subtract(u2, u1.clone());
+        let r = f.subtract(s2, s1.clone());
+        let h_squared = f.multiply(h.clone(), h.clone());
+        let h_cubed = f.multiply(h_squared.clone(), h.clone());
+        let u1_h_squared = f.multiply(u1, h_squared);
+
+        let x = f.subtract(f.subtract(f.multiply(r.clone(), r.clone()), h_cubed.clone()), f.add(u1_h_squared.clone(), u1_h_squared.clone()));
+        let y = f.subtract(f.multiply(r, f.subtract(u1_h_squared, x.clone())), f.multiply(s1, h_cubed));
+        let z = f.multiply(h, f.multiply(e1.z, e2.z));
+        JacobianPoint { x, y, z }
+    }
+
+    fn identity(&self) -> JacobianPoint<E> {
+        JacobianPoint { x: self.one(), y: self.one(), z: self.zero() }
+    }
+}
+
+impl<F: Field<E>, E: Clone + PartialEq> Group<Addition, JacobianPoint<E>> for EllipticCurve<F, E> {
+    fn inverse(&self, e: JacobianPoint<E>) -> JacobianPoint<E> {
+        JacobianPoint { y: <F as Group<Addition, E>>::inverse(&self.field, e.y), ..e }
+    }
+}
+
+impl<F: Field<E>, E: Clone + PartialEq> AbelianGroup<Addition, JacobianPoint<E>> for EllipticCurve<F, E> {}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::math::field::{Fp, PrimeField};
+    use crate::math::laws::{check_abelian_group, Elements};
+
+    fn points<E: Clone + PartialEq, F: Field<E>>(curve: &EllipticCurve<F, E>, field_elements: &[E]) -> Vec<Point<E>> {
+        let mut points = vec![Point::Infinity];
+        for x in field_elements {
+            for y in field_elements {
+                if let Ok(point) = curve.point(x.clone(), y.clone()) {
+                    points.push(point);
+                }
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn run_curve_arithmetic() {
+        // y^2 = x^3 + x + 1 mod 5 has the 9 points O, (0, 1), (0, 4), (2, 1), (2, 4), (3, 1),
+        // (3, 4), (4, 2) and (4, 3)
+        let curve = EllipticCurve::new(PrimeField::new(5u32), 1, 1);
+        let all = points(&curve, &[0, 1, 2, 3, 4]);
+        assert_eq!(all.len(), 9);
+
+        let p = curve.point(0, 1).unwrap();
+        assert_eq!(curve.apply(p.clone(), p.clone()), Point::Affine { x: 4, y: 2 });
+        assert_eq!(curve.apply(p.clone(), curve.inverse(p.clone())), Point::Infinity);
+        assert_eq!(curve.inverse(p.clone()), Point::Affine { x: 0, y: 4 });
+        assert_eq!(curve.scalar_mul(p.clone(), &9u32), Point::Infinity);
+        assert_eq!(curve.point(1, 1), Err(Error::NotOnCurve));
+        assert!(curve.is_on_curve(&Point::Infinity));
+
+        // a point with y = 0 is its own inverse
+        let curve = EllipticCurve::new(PrimeField::new(7u32), 0, 6);
+        let p = curve.point(1, 0).unwrap();
+        assert_eq!(curve.apply(p.clone(), p.clone()), Point::Infinity);
+        assert_eq!(curve.multiply(&p, &2u32), Point::Infinity);
+    }
+
+    #[test]
+    fn run_curve_group_laws() {
+        let field = PrimeField::new(23u32);
+        let curve = EllipticCurve::new(field, 1, 1);
+        let elements: Vec<u32> = (0..23).collect();
+        let all = points(&curve, &elements);
+        assert_eq!(all.len(), 28);
+        assert_eq!(check_abelian_group::<Addition, _, _>(&curve, &mut Elements::All(&all)), Ok(()));
+
+        // every point's order divides the number of points
+        for p in &all {
+            assert_eq!(curve.scalar_mul(p.clone(), &28u32), Point::Infinity);
+        }
+    }
+
+    #[test]
+    fn run_jacobian_coordinates() {
+        type F = Fp<1009>;
+        let curve = EllipticCurve::new(F::ZERO, F::new(2), F::new(3));
+        let field_elements: Vec<F> = (0..1009).map(F::new).collect();
+        let all = points(&curve, &field_elements);
+        assert_eq!(all.len(), 1068);
+
+        for (p, q) in all.iter().zip(all.iter().rev()).step_by(37) {
+            let sum = curve.apply(curve.to_jacobian(p), curve.to_jacobian(q));
+            assert_eq!(curve.to_affine(&sum), curve.apply(p.clone(), q.clone()));
+
+            let doubled = curve.apply(curve.to_jacobian(p), curve.to_jacobian(p));
+            assert_eq!(curve.to_affine(&doubled), curve.apply(p.clone(), p.clone()));
+
+            for n in [0u64, 1, 2, 3, 89, 1067, 1068, 5000] {
+                assert_eq!(curve.multiply(p, &n), curve.scalar_mul(p.clone(), &n));
+            }
+        }
+
+        // (X, Y, Z) and (t^2 X, t^3 Y, t Z) are the same point
+        let p = &all[10];
+        let mut jacobian = curve.to_jacobian(p);
+        let t = F::new(5);
+        jacobian = JacobianPoint { x: jacobian.x * t * t, y: jacobian.y * t * t * t, z: jacobian.z * t };
+        assert_eq!(curve.to_affine(&jacobian), *p);
+        assert_eq!(curve.to_affine(&curve.inverse(jacobian)), curve.inverse(p.clone()));
+    }
+
+    #[test]
+    fn run_singular_curves() {
+        // y^2 = x^3 has a cusp and y^2 = x^3 - 3x + 2 = (x - 1)^2 (x + 2) a node
+        assert_eq!(EllipticCurve::try_new(PrimeField::new(101u32), 0, 0).err(), Some(Error::SingularCurve));
+        assert_eq!(EllipticCurve::try_new(PrimeField::new(101u32), 98, 2).err(), Some(Error::SingularCurve));
+        assert!(EllipticCurve::try_new(PrimeField::new(101u32), 98, 3).is_ok());
+    }
+
+    #[test]
+    fn run_characteristic_two_and_three() {
+        assert_eq!(EllipticCurve::try_new(PrimeField::new(2u32), 0, 1).err(), Some(Error::UnsupportedCharacteristic));
+        assert_eq!(EllipticCurve::try_new(PrimeField::new(3u32), 1, 0).err(), Some(Error::UnsupportedCharacteristic));
+        assert_eq!(EllipticCurve::try_new(Fp::<3>::ZERO, Fp::new(2), Fp::new(1)).err(), Some(Error::UnsupportedCharacteristic));
+        assert!(EllipticCurve::try_new(PrimeField::new(5u32), 1, 1).is_ok());
+    }
+
+    #[test]
+    fn run_unreduced_coordinates() {
+        // (5, 1) satisfies the equation mod 5 as (0, 1) does but is not a field element, and
+        // adding the two would divide by x1 - x2 = 0
+        let curve = EllipticCurve::new(PrimeField::new(5u32), 1, 1);
+        assert_eq!(curve.point(5, 1).err(), Some(Error::NotOnCurve));
+        assert_eq!(curve.point(0, 6).err(), Some(Error::NotOnCurve));
+        assert!(!curve.is_on_curve(&Point::Affine { x: 5, y: 1 }));
+
+        // the coefficients are reduced so curves given either way are equal
+        let reduced = EllipticCurve::new(PrimeField::new(5u32), 6, 11);
+        assert_eq!((*reduced.a(), *reduced.b()), (1, 1));
+        assert_eq!(reduced, curve);
+    }
+
+    #[test]
+    #[should_panic(expected = "curve is singular")]
+    fn run_singular_curve_panics() {
+        EllipticCurve::new(PrimeField::new(7u64), 0, 0);
+    }
+
+}
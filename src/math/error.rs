@@ -0,0 +1,63 @@
+use std::fmt;
+
+/// Errors returned by the fallible constructors and operations in the math module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The modulus of a field is not a prime
+    NotPrime,
+    /// The modulus of a ring is zero
+    ZeroModulus,
+    /// Zero has no multiplicative inverse
+    DivisionByZero,
+    /// The element has no inverse because it shares a factor with the modulus
+    NotInvertible,
+    /// The moduli share a factor where they are required to be coprime
+    NotCoprime,
+    /// A system of congruences has no solution
+    InconsistentCongruences,
+    /// A requested bit length is too short or too long for the element type
+    InvalidBitLength,
+    /// A result such as a combined modulus does not fit in the element type
+    Overflow,
+    /// An element is not in the subgroup generated by another so has no discrete logarithm
+    NotInSubgroup,
+    /// The field has no root of unity of the order a transform needs
+    NoRootOfUnity,
+    /// Short Weierstrass curves need a field whose characteristic is not 2 or 3
+    UnsupportedCharacteristic,
+    /// The curve has a zero discriminant so its points do not form a group
+    SingularCurve,
+    /// The coordinates do not satisfy the curve equation
+    NotOnCurve,
+    /// The bytes are not a valid encoding of a point
+    InvalidEncoding,
+    /// The elements of an operation belong to different structures, such as fields with different moduli
+    DifferentStructures,
+    /// The number of values does not match the number of points they belong to
+    LengthMismatch,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotPrime => write!(f, "modulus is not a prime"),
+            Error::ZeroModulus => write!(f, "modulus is zero"),
+            Error::DivisionByZero => write!(f, "Cannot calculate inverse for zero"),
+            Error::NotInvertible => write!(f, "No inverse found"),
+            Error::NotCoprime => write!(f, "moduli are not coprime"),
+            Error::InconsistentCongruences => write!(f, "congruences have no common solution"),
+            Error::InvalidBitLength => write!(f, "invalid bit length"),
+            Error::Overflow => write!(f, "result does not fit in the element type"),
+            Error::NotInSubgroup => write!(f, "element is not in the subgroup"),
+            Error::NoRootOfUnity => write!(f, "no root of unity of the required order"),
+            Error::UnsupportedCharacteristic => write!(f, "field characteristic is 2 or 3"),
+            Error::SingularCurve => write!(f, "curve is singular"),
+            Error::NotOnCurve => write!(f, "point is not on the curve"),
+            Error::InvalidEncoding => write!(f, "invalid point encoding"),
+            Error::DifferentStructures => write!(f, "elements belong to different structures"),
+            Error::LengthMismatch => write!(f, "number of values does not match number of points"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
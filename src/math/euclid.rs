@@ -0,0 +1,161 @@
+use crate::math::integer::UnsignedInt;
+
+/// A Bézout coefficient stored as a magnitude and a sign so that unsigned types can be used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coefficient<E> {
+    pub magnitude: E,
+    pub negative: bool,
+}
+
+impl<E: UnsignedInt> Coefficient<E> {
+    /// Returns the coefficient as a representative in the range 0..m
+    pub fn reduce(&self, m: &E) -> E {
+        let r = self.magnitude.clone() % m.clone();
+        if self.negative && !r.is_zero() {
+            m.clone() - r
+        } else {
+            r
+        }
+    }
+}
+
+/// The result of the extended Euclidean algorithm which satisfies gcd = a * x + b * y
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bezout<E> {
+    pub gcd: E,
+    pub x: Coefficient<E>,
+    pub y: Coefficient<E>,
+}
+
+/// Runs the extended Euclidean algorithm returning gcd(a, b) and the Bézout coefficients x and y.
+///
+/// The coefficients of the remainder sequence alternate in sign so only their magnitudes are
+/// tracked, which are bounded by the inputs and therefore never overflow.
+pub fn extended_gcd<E: UnsignedInt>(a: E, b: E) -> Bezout<E> {
+    let (mut r0, mut r1) = (a, b);
+    let (mut s0, mut s1) = (E::one(), E::zero());
+    let (mut t0, mut t1) = (E::zero(), E::one());
+    let mut odd_step = false;
+
+    while !r1.is_zero() {
+        let (q, r) = r0.div_rem(&r1);
+        r0 = std::mem::replace(&mut r1, r);
+
+        let s = s0 + q.clone() * s1.clone();
+        s0 = std::mem::replace(&mut s1, s);
+
+        let t = t0 + q * t1.clone();
+        t0 = std::mem::replace(&mut t1, t);
+
+        odd_step = !odd_step;
+    }
+
+    // s_i has the sign (-1)^i and t_i has the sign (-1)^(i + 1)
+    let x_negative = odd_step && !s0.is_zero();
+    let y_negative = !odd_step && !t0.is_zero();
+
+    Bezout {
+        gcd: r0,
+        x: Coefficient { magnitude: s0, negative: x_negative },
+        y: Coefficient { magnitude: t0, negative: y_negative },
+    }
+}
+
+/// Returns the greatest common divisor of a and b
+pub fn gcd<E: UnsignedInt>(mut a: E, mut b: E) -> E {
+    while !b.is_zero() {
+        let r = a % b.clone();
+        a = std::mem::replace(&mut b, r);
+    }
+    a
+}
+
+/// Returns the inverse of a modulo m or None if a and m are not coprime
+pub fn mod_inverse<E: UnsignedInt>(a: E, m: E) -> Option<E> {
+    if m.is_zero() {
+        return None;
+    }
+
+    let bezout = extended_gcd(a % m.clone(), m.clone());
+    if !bezout.gcd.is_one() {
+        return None;
+    }
+
+    Some(bezout.x.reduce(&m))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::math::BigUint;
+
+    /// Checks gcd = a * x + b * y using signed arithmetic
+    fn check_bezout(a: u64, b: u64) {
+        let result = extended_gcd(a, b);
+        let signed = |c: &Coefficient<u64>| if c.negative { -(c.magnitude as i128) } else { c.magnitude as i128 };
+        let lhs = a as i128 * signed(&result.x) + b as i128 * signed(&result.y);
+        assert_eq!(lhs, result.gcd as i128, "a = {}, b = {}", a, b);
+        assert_eq!(result.gcd, gcd(a, b));
+    }
+
+    #[test]
+    fn run_extended_gcd() {
+        let result = extended_gcd(240u32, 46u32);
+        assert_eq!(result.gcd, 2);
+        // 240 * -9 + 46 * 47 = 2
+        assert_eq!(result.x, Coefficient { magnitude: 9, negative: true });
+        assert_eq!(result.y, Coefficient { magnitude: 47, negative: false });
+
+        for a in 0..50 {
+            for b in 0..50 {
+                check_bezout(a, b);
+            }
+        }
+        check_bezout(u64::MAX, u64::MAX - 1);
+        check_bezout(u64::MAX, 1 << 63);
+        check_bezout(0xffff_ffff_0000_0001, 0x1234_5678_9abc_def1);
+    }
+
+    #[test]
+    fn run_mod_inverse() {
+        assert_eq!(mod_inverse(3u32, 11), Some(4));
+        assert_eq!(mod_inverse(10u32, 11), Some(10));
+        assert_eq!(mod_inverse(0u32, 11), None);
+        assert_eq!(mod_inverse(6u32, 9), None);
+        assert_eq!(mod_inverse(5u32, 0), None);
+
+        // every non zero element of a prime field has an inverse
+        let p = 65521u32;
+        for a in 1..p {
+            let inverse = mod_inverse(a, p).unwrap();
+            assert_eq!((a as u64 * inverse as u64) % p as u64, 1);
+        }
+
+        // Mersenne prime 2^61 - 1
+        let p = (1u64 << 61) - 1;
+        let a = 0x0123_4567_89ab_cdefu64;
+        let inverse = mod_inverse(a, p).unwrap();
+        assert_eq!((a as u128 * inverse as u128) % p as u128, 1);
+
+        // u128 values use the full width without overflowing
+        let p = u128::MAX - 158; // largest prime below 2^128
+        let a = u128::MAX - 1000;
+        let inverse = mod_inverse(a, p).unwrap();
+        assert!((BigUint::from(a) * BigUint::from(inverse) % BigUint::from(p)).is_one());
+    }
+
+    #[test]
+    fn run_mod_inverse_biguint() {
+        let p = BigUint::from_hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff").unwrap();
+        let a = BigUint::from_hex("6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296").unwrap();
+
+        let inverse = mod_inverse(a.clone(), p.clone()).unwrap();
+        assert!((&a * &inverse % &p).is_one());
+
+        let bezout = extended_gcd(a.clone(), p.clone());
+        assert!(bezout.gcd.is_one());
+        assert_eq!(bezout.x.reduce(&p), inverse);
+    }
+
+}
@@ -0,0 +1,414 @@
+use crate::math::euclid::gcd;
+use crate::math::integer::UnsignedInt;
+use crate::math::number_theory::isqrt;
+use crate::math::primes::is_prime;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Trial division removes every prime factor below this bound before the Pollard methods run
+const TRIAL_DIVISION_BOUND: u32 = 1 << 12;
+
+/// Pollard's p-1 method finds p when every prime power dividing p - 1 is below this bound
+const P_MINUS_ONE_BOUND: u32 = 10_000;
+
+/// The number of values the segmented sieve covers at a time
+const SEGMENT_SIZE: u64 = 1 << 15;
+
+/// The prime factorisation of a number as a multiset of primes, each with its multiplicity.
+///
+/// Iterating yields (prime, exponent) pairs in increasing order of prime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Factorization<E>(BTreeMap<E, u32>);
+
+impl<E: UnsignedInt> Factorization<E> {
+    pub fn new() -> Self {
+        Factorization(BTreeMap::new())
+    }
+
+    /// Adds the prime p with the given multiplicity
+    pub fn insert(&mut self, p: E, exponent: u32) {
+        if exponent > 0 {
+            *self.0.entry(p).or_insert(0) += exponent;
+        }
+    }
+
+    /// Returns the exponent of p which is zero when p is not a factor
+    pub fn multiplicity(&self, p: &E) -> u32 {
+        self.0.get(p).copied().unwrap_or(0)
+    }
+
+    /// Returns the (prime, exponent) pairs in increasing order of prime
+    pub fn iter(&self) -> impl Iterator<Item = (&E, u32)> {
+        self.0.iter().map(|(p, &k)| (p, k))
+    }
+
+    /// Returns the distinct primes in increasing order
+    pub fn primes(&self) -> impl Iterator<Item = &E> {
+        self.0.keys()
+    }
+
+    /// Returns the number of distinct primes
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Multiplies the prime powers back together
+    pub fn value(&self) -> E {
+        self.iter().fold(E::one(), |acc, (p, k)| (0..k).fold(acc, |acc, _| acc * p.clone()))
+    }
+
+    /// Returns the (prime, exponent) pairs in increasing order of prime
+    pub fn to_vec(&self) -> Vec<(E, u32)> {
+        self.iter().map(|(p, k)| (p.clone(), k)).collect()
+    }
+}
+
+impl<E: UnsignedInt> Default for Factorization<E> {
+    fn default() -> Self {
+        Factorization::new()
+    }
+}
+
+impl<E> IntoIterator for Factorization<E> {
+    type Item = (E, u32);
+    type IntoIter = std::collections::btree_map::IntoIter<E, u32>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<E: UnsignedInt> fmt::Display for Factorization<E> {
+    /// Formats as 2^3 * 3^2 * 5, or 1 when there are no factors
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "1");
+        }
+        for (i, (p, k)) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, " * ")?;
+            }
+            write!(f, "{}", p)?;
+            if k > 1 {
+                write!(f, "^{}", k)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the primes in low..high using a segmented Sieve of Eratosthenes.
+///
+/// Only the primes up to the square root of high are sieved in full, the range itself is crossed
+/// off one segment at a time so the memory used is bounded by the segment size.
+pub fn primes_in_range(low: u64, high: u64) -> Vec<u64> {
+    let mut primes = Vec::new();
+    if high <= low {
+        return primes;
+    }
+
+    let base = small_primes(isqrt(&(high - 1)) + 1);
+    let mut segment_low = low.max(2);
+    while segment_low < high {
+        let segment_high = high.min(segment_low.saturating_add(SEGMENT_SIZE));
+        let mut composite = vec![false; (segment_high - segment_low) as usize];
+        for &p in base.iter().take_while(|&&p| p * p < segment_high) {
+            // start from the first multiple of p in the segment but never cross off p itself
+            let first = (p * p).max(segment_low.div_ceil(p) * p);
+            for m in (first..segment_high).step_by(p as usize) {
+                composite[(m - segment_low) as usize] = true;
+            }
+        }
+        primes.extend((segment_low..segment_high).filter(|&n| !composite[(n - segment_low) as usize]));
+        segment_low = segment_high;
+    }
+    primes
+}
+
+/// Returns the primes below bound using a plain Sieve of Eratosthenes
+fn small_primes(bound: u64) -> Vec<u64> {
+    let mut composite = vec![false; bound as usize];
+    let mut primes = Vec::new();
+    for i in 2..bound {
+        if !composite[i as usize] {
+            primes.push(i);
+            (i * i..bound).step_by(i as usize).for_each(|j| composite[j as usize] = true);
+        }
+    }
+    primes
+}
+
+/// An unbounded iterator over the primes in increasing order which sieves one segment at a time
+pub struct Primes {
+    segment: std::vec::IntoIter<u64>,
+    next_low: u64,
+}
+
+impl Primes {
+    pub fn new() -> Self {
+        Primes { segment: Vec::new().into_iter(), next_low: 0 }
+    }
+}
+
+impl Default for Primes {
+    fn default() -> Self {
+        Primes::new()
+    }
+}
+
+impl Iterator for Primes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        loop {
+            if let Some(p) = self.segment.next() {
+                return Some(p);
+            }
+            if self.next_low == u64::MAX {
+                return None;
+            }
+            let high = self.next_low.saturating_add(SEGMENT_SIZE);
+            self.segment = primes_in_range(self.next_low, high).into_iter();
+            self.next_low = high;
+        }
+    }
+}
+
+/// Divides out every prime below bound returning those factors along with the remaining cofactor
+pub fn trial_division<E: UnsignedInt>(n: &E, bound: u32) -> (Factorization<E>, E) {
+    let mut factors = Factorization::new();
+    let mut n = n.clone();
+    if n.is_zero() {
+        return (factors, n);
+    }
+
+    // small values need no primes beyond their square root
+    let limit = n.to_u64().map_or(bound as u64, |n| (bound as u64).min(isqrt(&n) + 1));
+    for p in primes_in_range(2, limit) {
+        let p = E::from_u32(p as u32);
+        // compare p with n / p rather than p * p with n so the square cannot overflow
+        if p > n.clone() / p.clone() {
+            break;
+        }
+        let mut exponent = 0;
+        loop {
+            let (q, r) = n.div_rem(&p);
+            if !r.is_zero() {
+                break;
+            }
+            n = q;
+            exponent += 1;
+        }
+        factors.insert(p, exponent);
+    }
+
+    // a cofactor below the square of the last prime tried is itself prime
+    if !n.is_one() && n.to_u64().is_some_and(|n| n < bound as u64 * bound as u64) {
+        factors.insert(n, 1);
+        n = E::one();
+    }
+    (factors, n)
+}
+
+/// Pollard's p-1 method finds a prime factor p of n when every prime power dividing p - 1 is at
+/// most bound, as then a^(p-1) divides the exponent and gcd(a^M - 1, n) picks out p.
+///
+/// Returns a non-trivial factor of n if one is found.
+pub fn pollard_p_minus_one<E: UnsignedInt>(n: &E, bound: u32) -> Option<E> {
+    if n.is_even() {
+        return (*n > E::from_u32(2)).then(|| E::from_u32(2));
+    }
+
+    // M is the product of the largest power of each prime not above the bound
+    let mut a = E::from_u32(2);
+    for q in primes_in_range(2, bound as u64 + 1) {
+        let mut power = q;
+        while power <= bound as u64 / q {
+            power *= q;
+        }
+        a = a.pow_mod(&E::from_u32(power as u32), n);
+    }
+
+    let d = gcd(a.sub_mod(&E::one(), n), n.clone());
+    (!d.is_one() && d != *n).then_some(d)
+}
+
+/// Pollard's rho method with Brent's cycle detection. The sequence x -> x^2 + c mod n eventually
+/// cycles mod each prime factor p after about sqrt(p) steps which reveals p through a gcd.
+///
+/// The differences are multiplied together so only one gcd is taken per batch of steps. Returns a
+/// non-trivial factor of n if one is found, otherwise a different c should be tried.
+pub fn pollard_rho_brent<E: UnsignedInt>(n: &E, c: &E) -> Option<E> {
+    const BATCH: usize = 128;
+
+    if n.is_even() {
+        return (*n > E::from_u32(2)).then(|| E::from_u32(2));
+    }
+
+    let f = |x: &E| x.mul_mod(x, n).add_mod(c, n);
+    let difference = |a: &E, b: &E| if a > b { a.clone() - b.clone() } else { b.clone() - a.clone() };
+
+    let mut y = E::from_u32(2);
+    let mut x = y.clone();
+    let mut saved = y.clone();
+    let mut q = E::one();
+    let mut g = E::one();
+    let mut r = 1;
+
+    // x holds the value at the last power of two and y walks up to r steps beyond it
+    while g.is_one() {
+        x = y.clone();
+        for _ in 0..r {
+            y = f(&y);
+        }
+        let mut k = 0;
+        while k < r && g.is_one() {
+            saved = y.clone();
+            for _ in 0..BATCH.min(r - k) {
+                y = f(&y);
+                q = q.mul_mod(&difference(&x, &y), n);
+            }
+            g = gcd(q.clone(), n.clone());
+            k += BATCH;
+        }
+        r *= 2;
+    }
+
+    // the batch product reached zero so step through it one gcd at a time
+    if g == *n {
+        loop {
+            saved = f(&saved);
+            g = gcd(difference(&x, &saved), n.clone());
+            if !g.is_one() {
+                break;
+            }
+        }
+    }
+
+    (g != *n).then_some(g)
+}
+
+/// Returns the prime factorisation of n. Zero has no factorisation and one has no factors.
+///
+/// Small factors are removed by trial division, the cofactor is then split by Pollard's p-1
+/// method or failing that Pollard's rho until every part is prime.
+pub fn factor<E: UnsignedInt>(n: &E) -> Factorization<E> {
+    let (mut factors, cofactor) = trial_division(n, TRIAL_DIVISION_BOUND);
+    if !cofactor.is_zero() {
+        split(&mut factors, cofactor);
+    }
+    factors
+}
+
+/// Adds the prime factors of n which has no factor below the trial division bound
+fn split<E: UnsignedInt>(factors: &mut Factorization<E>, n: E) {
+    if n.is_one() {
+        return;
+    }
+    if is_prime(&n) {
+        factors.insert(n, 1);
+        return;
+    }
+
+    let d = pollard_p_minus_one(&n, P_MINUS_ONE_BOUND)
+        .or_else(|| (1..).find_map(|c| pollard_rho_brent(&n, &E::from_u32(c))))
+        .unwrap();
+    let cofactor = n / d.clone();
+    split(factors, d);
+    split(factors, cofactor);
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::math::primes::is_prime_u64;
+    use crate::math::BigUint;
+
+    #[test]
+    fn run_primes() {
+        let first: Vec<u64> = Primes::new().take(10).collect();
+        assert_eq!(first, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+
+        // there are 78498 primes below a million which spans many segments
+        assert_eq!(Primes::new().take_while(|&p| p < 1_000_000).count(), 78498);
+        assert_eq!(primes_in_range(0, 1_000_000).len(), 78498);
+
+        assert_eq!(primes_in_range(10, 30), vec![11, 13, 17, 19, 23, 29]);
+        assert_eq!(primes_in_range(30, 10), vec![]);
+
+        let low = 1_000_000_000_000;
+        let primes = primes_in_range(low, low + 1000);
+        assert_eq!(primes.len(), 37);
+        assert!(primes.iter().all(|&p| is_prime_u64(p)));
+    }
+
+    #[test]
+    fn run_trial_division() {
+        let (factors, cofactor) = trial_division(&(360u64 * 65521 * 65537), 1000);
+        assert_eq!(factors.to_vec(), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(cofactor, 65521 * 65537);
+
+        // a cofactor below the square of the bound is prime
+        let (factors, cofactor) = trial_division(&(8u32 * 997), 1000);
+        assert_eq!(factors.to_vec(), vec![(2, 3), (997, 1)]);
+        assert_eq!(cofactor, 1);
+    }
+
+    #[test]
+    fn run_pollard_methods() {
+        // Brent's rho splits a product of two 32 bit primes
+        let n = 4_294_967_291u64 * 4_294_967_279;
+        let d = (1..).find_map(|c| pollard_rho_brent(&n, &c)).unwrap();
+        assert!(d == 4_294_967_291 || d == 4_294_967_279);
+
+        // p - 1 = 2 * 37 * 137 * 229 * 277 * 991 * 1543 * 1879 is smooth but q - 1 has the large
+        // factor 48912491 so only p is found, far faster than rho could find a 61 bit factor
+        let p = 1_847_710_381_532_449_559u128;
+        let q = 1_152_921_504_606_847_009u128;
+        assert_eq!(pollard_p_minus_one(&(p * q), P_MINUS_ONE_BOUND), Some(p));
+        assert_eq!(pollard_p_minus_one(&q, P_MINUS_ONE_BOUND), None);
+
+        assert_eq!(pollard_rho_brent(&12u32, &1), Some(2));
+    }
+
+    #[test]
+    fn run_factor() {
+        assert_eq!(factor(&360u32).to_vec(), vec![(2, 3), (3, 2), (5, 1)]);
+        assert!(factor(&1u32).is_empty());
+        assert!(factor(&0u32).is_empty());
+        assert_eq!(factor(&97u64).to_vec(), vec![(97, 1)]);
+        assert_eq!(factor(&(u32::MAX - 4)).to_vec(), vec![(u32::MAX - 4, 1)]);
+
+        let factors = factor(&u64::MAX);
+        assert_eq!(factors.to_vec(), vec![(3, 1), (5, 1), (17, 1), (257, 1), (641, 1), (65537, 1), (6_700_417, 1)]);
+        assert_eq!(factors.value(), u64::MAX);
+        assert_eq!(factors.to_string(), "3 * 5 * 17 * 257 * 641 * 65537 * 6700417");
+        assert_eq!(factors.multiplicity(&641), 1);
+        assert_eq!(factors.multiplicity(&7), 0);
+
+        let n = 65521u128.pow(3) * 4_294_967_291;
+        let factors = factor(&n);
+        assert_eq!(factors.to_string(), "65521^3 * 4294967291");
+        assert_eq!(factors.value(), n);
+
+        let p = 1_847_710_381_532_449_559u128;
+        let q = 1_152_921_504_606_847_009u128;
+        assert_eq!(factor(&(p * q)).to_vec(), vec![(q, 1), (p, 1)]);
+
+        // 30! has every prime up to 30 as a factor
+        let n = (1..=30u32).fold(BigUint::one(), |acc, i| acc * BigUint::from(i));
+        assert_eq!(factor(&n).to_string(), "2^26 * 3^14 * 5^7 * 7^4 * 11^2 * 13^2 * 17 * 19 * 23 * 29");
+
+        let n = BigUint::from((1u64 << 61) - 1) * BigUint::from(4_294_967_291u32) * BigUint::from(65521u32).pow(2);
+        let factors = factor(&n);
+        assert_eq!(factors.len(), 3);
+        assert_eq!(factors.multiplicity(&BigUint::from(65521u32)), 2);
+        assert_eq!(factors.value(), n);
+    }
+
+}
@@ -1,106 +1,332 @@
-use crate::math::group::{AbelianGroup, Addition, Group, Multiplication};
+use crate::math::euclid;
+use crate::math::group::{AbelianGroup, Addition, Group, Monoid, Multiplication};
+use crate::math::integer::UnsignedInt;
+use crate::math::number_theory::legendre;
+use crate::math::primes::{self, is_prime_u64};
+use crate::math::ntt;
+use crate::math::ring::{self, Ring};
+use crate::math::roots;
+use crate::math::Error;
 
 /// A Field is an abelian group over addition and multiplication and distributive
 /// for multiplication with respect to addition.
-trait Field<E>: AbelianGroup<Addition, E> + AbelianGroup<Multiplication, E> {
-    /// Supports addition
-    fn add(&self, e1: E, e2: E) -> E;
-    /// Supports subtraction which is the same as addition with the inverse
-    fn subtract(&self, e1: E, e2: E) -> E;
-    /// Supports multiplication
-    fn multiply(&self, e1: E, e2: E) -> E;
+///
+/// A field is a ring so addition, subtraction and multiplication come from the Ring supertrait.
+pub trait Field<E>: Ring<E> + AbelianGroup<Multiplication, E> {
     /// Supports division where the divisor is not equal to zero
-    fn divide(&self, e1: E, e2: E) -> E;
+    fn divide(&self, e1: E, e2: E) -> E {
+        let inverse = <Self as Group<Multiplication, E>>::inverse(self, e2);
+        self.multiply(e1, inverse)
+    }
+    /// Supports division returning an error rather than panicking when the divisor is zero
+    fn checked_divide(&self, e1: E, e2: E) -> Result<E, Error> {
+        let inverse = <Self as Group<Multiplication, E>>::checked_inverse(self, e2)?;
+        Ok(self.multiply(e1, inverse))
+    }
 }
 
 /// The natural numbers mod p where p is a prime form a field.
-struct PrimeField(u32);
-
-impl PrimeField {
+///
+/// The element type E sets the width of the modulus. Sums and products are reduced without
+/// overflowing so the full range of u32, u64, u128 or BigUint can be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrimeField<E>(E);
+
+impl<E: UnsignedInt> PrimeField<E> {
+
+    pub fn new(modulus: E) -> Self {
+        match PrimeField::try_new(modulus) {
+            Ok(field) => field,
+            Err(e) => panic!("{}", e),
+        }
+    }
 
-    #[allow(dead_code)]
-    fn new(modulus: u32) -> Self {
-        if !primes::is_prime(modulus.into()) {
-            panic!("modulus is not a prime")
+    /// Returns an error rather than panicking if the modulus is not a prime
+    pub fn try_new(modulus: E) -> Result<Self, Error> {
+        if !primes::is_prime(&modulus) {
+            return Err(Error::NotPrime);
         }
 
-        PrimeField(modulus)
+        Ok(PrimeField(modulus))
     }
-}
 
+    /// Returns the prime modulus p
+    pub fn modulus(&self) -> &E {
+        &self.0
+    }
+}
 
-impl AbelianGroup<Addition, u32> for PrimeField {}
+impl<E: UnsignedInt> AbelianGroup<Addition, E> for PrimeField<E> {}
 
-impl Group<Addition, u32> for PrimeField {
-    fn apply(&self, e1: u32, e2: u32) -> u32 {
-        (e1 + e2) % self.0
+impl<E: UnsignedInt> Monoid<Addition, E> for PrimeField<E> {
+    fn apply(&self, e1: E, e2: E) -> E {
+        e1.add_mod(&e2, &self.0)
     }
 
-    fn identity(&self) -> u32 {
-        0
+    fn identity(&self) -> E {
+        E::zero()
     }
+}
 
-    fn inverse(&self, e: u32) -> u32 {
-        self.0 - e % self.0
+impl<E: UnsignedInt> Group<Addition, E> for PrimeField<E> {
+    fn inverse(&self, e: E) -> E {
+        E::zero().sub_mod(&e, &self.0)
     }
 }
 
-impl AbelianGroup<Multiplication, u32> for PrimeField {}
+impl<E: UnsignedInt> AbelianGroup<Multiplication, E> for PrimeField<E> {}
 
-impl Group<Multiplication, u32> for PrimeField {
-    fn apply(&self, e1: u32, e2: u32) -> u32 {
-        (e1 * e2) % self.0
+impl<E: UnsignedInt> Monoid<Multiplication, E> for PrimeField<E> {
+    fn apply(&self, e1: E, e2: E) -> E {
+        e1.mul_mod(&e2, &self.0)
     }
 
-    fn identity(&self) -> u32 {
-        1
+    fn identity(&self) -> E {
+        E::one()
     }
+}
 
-    fn inverse(&self, e: u32) -> u32 {
-        if e % self.0 == 0 {
-            panic!("Cannot calculate inverse for zero")
+impl<E: UnsignedInt> Group<Multiplication, E> for PrimeField<E> {
+    fn inverse(&self, e: E) -> E {
+        match <Self as Group<Multiplication, E>>::checked_inverse(self, e) {
+            Ok(inverse) => inverse,
+            Err(e) => panic!("{}", e),
         }
+    }
 
-        // this trial and error method to calculate the inverse is not efficient
-        // in another post we will use the Extended Euclidean Algorithm to solve this
-        for i in 0..self.0 {
-            if (e * i) % self.0 == 1 {
-                return i;
+    fn checked_inverse(&self, e: E) -> Result<E, Error> {
+        if (e.clone() % self.0.clone()).is_zero() {
+            return Err(Error::DivisionByZero);
+        }
+
+        // the Extended Euclidean Algorithm finds the inverse in a logarithmic number of steps
+        euclid::mod_inverse(e, self.0.clone()).ok_or(Error::NotInvertible)
+    }
+}
+
+impl<E: UnsignedInt> Ring<E> for PrimeField<E> {
+    /// Switches from the schoolbook method to the number theoretic transform for long inputs when
+    /// the field has a root of unity of large enough power of two order
+    fn convolve(&self, a: &[E], b: &[E]) -> Vec<E> {
+        if a.len().min(b.len()) >= ntt::THRESHOLD {
+            if let Ok(c) = ntt::convolve(self, a, b) {
+                return c;
             }
         }
+        ring::schoolbook_convolve(self, a, b)
+    }
+}
+
+impl<E: UnsignedInt> Field<E> for PrimeField<E> {}
+
+/// An element of the field of integers mod P where the prime modulus is part of the type.
+///
+/// Mixing elements of different fields is a type error and the std::ops operators can be
+/// used directly. A composite P fails to compile as soon as an element is constructed.
+/// Because the modulus lives in the type every element can also act as the field structure,
+/// so Fp<P> implements Field<Fp<P>> and ignores self.
+///
+/// ```compile_fail
+/// use blog_code_samples::math::field::Fp;
+/// let _ = Fp::<11>::new(1) + Fp::<13>::new(1);
+/// ```
+///
+/// ```compile_fail
+/// use blog_code_samples::math::field::Fp;
+/// let _ = Fp::<12>::new(1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fp<const P: u64>(u64);
+
+impl<const P: u64> Fp<P> {
+    /// Evaluated at compile time for each modulus that is used
+    const PRIME: () = assert!(is_prime_u64(P), "modulus is not a prime");
+
+    pub const ZERO: Self = Fp::new(0);
+    pub const ONE: Self = Fp::new(1);
+
+    pub const fn new(value: u64) -> Self {
+        let () = Self::PRIME;
+        Fp(value % P)
+    }
+
+    /// Returns the canonical representative in the range 0..P
+    pub const fn value(&self) -> u64 {
+        self.0
+    }
+
+    pub const fn modulus() -> u64 {
+        P
+    }
+
+    /// Returns the multiplicative inverse
+    pub fn inverse(&self) -> Self {
+        match self.checked_inverse() {
+            Ok(inverse) => inverse,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Returns the multiplicative inverse or an error for zero
+    pub fn checked_inverse(&self) -> Result<Self, Error> {
+        if self.0 == 0 {
+            return Err(Error::DivisionByZero);
+        }
+
+        euclid::mod_inverse(self.0, P).map(Fp).ok_or(Error::NotInvertible)
+    }
+
+    /// Returns true if the element has a square root
+    pub fn is_square(&self) -> bool {
+        legendre(&self.0, &P) >= 0
+    }
+
+    /// Returns the smaller of the two square roots or None if there are none
+    pub fn sqrt(&self) -> Option<Self> {
+        roots::sqrt_mod_prime(&self.0, &P).map(Fp)
+    }
 
-        panic!("No inverse found");
+    /// Returns a cube root or None if there are none
+    pub fn cbrt(&self) -> Option<Self> {
+        roots::cbrt_mod_prime(&self.0, &P).map(Fp)
     }
 }
 
-impl Field<u32> for PrimeField {
-    fn add(&self, e1: u32, e2: u32) -> u32 {
-        <dyn AbelianGroup<Addition, u32>>::apply(self, e1, e2)
+impl<const P: u64> Default for Fp<P> {
+    fn default() -> Self {
+        Fp::ZERO
     }
+}
+
+impl<const P: u64> From<u64> for Fp<P> {
+    fn from(value: u64) -> Self {
+        Fp::new(value)
+    }
+}
+
+impl<const P: u64> std::fmt::Display for Fp<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<const P: u64> std::ops::Add for Fp<P> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Fp(self.0.add_mod(&rhs.0, &P))
+    }
+}
+
+impl<const P: u64> std::ops::Sub for Fp<P> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Fp(self.0.sub_mod(&rhs.0, &P))
+    }
+}
+
+impl<const P: u64> std::ops::Mul for Fp<P> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Fp(self.0.mul_mod(&rhs.0, &P))
+    }
+}
+
+impl<const P: u64> std::ops::Div for Fp<P> {
+    type Output = Self;
 
-    fn subtract(&self, e1: u32, e2: u32) -> u32 {
-        let inverse = <dyn AbelianGroup<Addition, u32>>::inverse(self,e2);
-        <dyn AbelianGroup<Addition, u32>>::apply(self, e1, inverse)
+    fn div(self, rhs: Self) -> Self {
+        Fp(self.0.mul_mod(&rhs.inverse().0, &P))
     }
+}
+
+impl<const P: u64> std::ops::Neg for Fp<P> {
+    type Output = Self;
 
-    fn multiply(&self, e1: u32, e2: u32) -> u32 {
-        <dyn AbelianGroup<Multiplication, u32>>::apply(self, e1, e2)
+    fn neg(self) -> Self {
+        Fp::ZERO - self
     }
+}
+
+impl<const P: u64> std::ops::AddAssign for Fp<P> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
 
-    fn divide(&self, e1: u32, e2: u32) -> u32 {
-        let inverse = <dyn AbelianGroup<Multiplication, u32>>::inverse(self,e2);
-        <dyn AbelianGroup<Multiplication, u32>>::apply(self, e1, inverse)
+impl<const P: u64> std::ops::SubAssign for Fp<P> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
     }
 }
 
+impl<const P: u64> std::ops::MulAssign for Fp<P> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const P: u64> std::ops::DivAssign for Fp<P> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<const P: u64> AbelianGroup<Addition, Fp<P>> for Fp<P> {}
+
+impl<const P: u64> Monoid<Addition, Fp<P>> for Fp<P> {
+    fn apply(&self, e1: Fp<P>, e2: Fp<P>) -> Fp<P> {
+        e1 + e2
+    }
+
+    fn identity(&self) -> Fp<P> {
+        Fp::ZERO
+    }
+}
+
+impl<const P: u64> Group<Addition, Fp<P>> for Fp<P> {
+    fn inverse(&self, e: Fp<P>) -> Fp<P> {
+        -e
+    }
+}
+
+impl<const P: u64> AbelianGroup<Multiplication, Fp<P>> for Fp<P> {}
+
+impl<const P: u64> Monoid<Multiplication, Fp<P>> for Fp<P> {
+    fn apply(&self, e1: Fp<P>, e2: Fp<P>) -> Fp<P> {
+        e1 * e2
+    }
+
+    fn identity(&self) -> Fp<P> {
+        Fp::ONE
+    }
+}
+
+impl<const P: u64> Group<Multiplication, Fp<P>> for Fp<P> {
+    fn inverse(&self, e: Fp<P>) -> Fp<P> {
+        Fp::inverse(&e)
+    }
+
+    fn checked_inverse(&self, e: Fp<P>) -> Result<Fp<P>, Error> {
+        Fp::checked_inverse(&e)
+    }
+}
+
+impl<const P: u64> Ring<Fp<P>> for Fp<P> {}
+
+impl<const P: u64> Field<Fp<P>> for Fp<P> {}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::math::BigUint;
 
     #[test]
     fn run_prime_field() {
-        let field = PrimeField::new(11);
+        let field = PrimeField::new(11u32);
 
         let result = field.add(3, 10);
         assert_eq!(2, result);
@@ -120,6 +346,173 @@ mod tests {
         }
     }
 
+    /// Checks the field axioms for every pair and triple of elements and compares the
+    /// results with BigUint arithmetic which cannot overflow
+    fn check_field_axioms<E: UnsignedInt + Copy + Into<BigUint>>(field: &PrimeField<E>, elements: &[E]) {
+        let p: BigUint = (*field.modulus()).into();
+        let zero = E::zero();
+        let one = E::one();
+
+        for &a in elements {
+            // Identity and inverse, including the additive inverse of zero being zero
+            assert_eq!(field.add(a, zero), a);
+            assert_eq!(field.multiply(a, one), a);
+            assert_eq!(field.add(a, <dyn AbelianGroup<Addition, E>>::inverse(field, a)), zero);
+            if a != zero {
+                let inverse = <dyn AbelianGroup<Multiplication, E>>::inverse(field, a);
+                assert_eq!(field.multiply(a, inverse), one);
+            }
+
+            for &b in elements {
+                let (big_a, big_b): (BigUint, BigUint) = (a.into(), b.into());
+                assert_eq!(field.add(a, b).into(), (&big_a + &big_b) % &p);
+                assert_eq!(field.multiply(a, b).into(), (&big_a * &big_b) % &p);
+                assert_eq!(field.add(field.subtract(a, b), b), a);
+
+                // Commutativity
+                assert_eq!(field.add(a, b), field.add(b, a));
+                assert_eq!(field.multiply(a, b), field.multiply(b, a));
+
+                for &c in elements {
+                    // Associativity
+                    assert_eq!(field.add(a, field.add(b, c)), field.add(field.add(a, b), c));
+                    assert_eq!(field.multiply(a, field.multiply(b, c)), field.multiply(field.multiply(a, b), c));
+
+                    // Distributivity
+                    assert_eq!(field.multiply(a, field.add(b, c)), field.add(field.multiply(a, b), field.multiply(a, c)));
+                }
+            }
+        }
+    }
+
+    fn elements_near_top<E: UnsignedInt + Copy>(p: E) -> Vec<E> {
+        let two = E::from_u32(2);
+        let half = p / two;
+        vec![E::zero(), E::one(), two, half, half + E::one(), p - two, p - E::one()]
+    }
+
+    #[test]
+    fn run_prime_field_axioms_small() {
+        let field = PrimeField::new(13u32);
+        let elements: Vec<u32> = (0..13).collect();
+        check_field_axioms(&field, &elements);
+    }
+
+    #[test]
+    fn run_prime_field_axioms_near_the_top_of_each_width() {
+        // the largest primes below 2^32, 2^64 and 2^128
+        let p = u32::MAX - 4;
+        check_field_axioms(&PrimeField::new(p), &elements_near_top(p));
+
+        let p = u64::MAX - 58;
+        check_field_axioms(&PrimeField::new(p), &elements_near_top(p));
+
+        let p = u128::MAX - 158;
+        check_field_axioms(&PrimeField::new(p), &elements_near_top(p));
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus is not a prime")]
+    fn run_prime_field_carmichael_modulus() {
+        // 1171 * 2341 * 3511 passes the Fermat test for every base coprime to it
+        PrimeField::new(9_624_742_921u64);
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus is not a prime")]
+    fn run_prime_field_composite_modulus() {
+        PrimeField::new(u64::MAX);
+    }
+
+    #[test]
+    fn run_prime_field_fallible() {
+        assert!(PrimeField::try_new(11u32).is_ok());
+        assert_eq!(PrimeField::try_new(12u32).err(), Some(Error::NotPrime));
+        assert_eq!(PrimeField::try_new(0u64).err(), Some(Error::NotPrime));
+        assert_eq!(PrimeField::try_new(1u64).err(), Some(Error::NotPrime));
+
+        let field = PrimeField::new(11u32);
+        assert_eq!(field.checked_divide(10, 2), Ok(5));
+        assert_eq!(field.checked_divide(10, 0), Err(Error::DivisionByZero));
+        assert_eq!(field.checked_divide(10, 22), Err(Error::DivisionByZero));
+        assert_eq!(<dyn AbelianGroup<Multiplication, u32>>::checked_inverse(&field, 3), Ok(4));
+        assert_eq!(<dyn AbelianGroup<Multiplication, u32>>::checked_inverse(&field, 0), Err(Error::DivisionByZero));
+
+        // additive inverses always exist
+        assert_eq!(<dyn AbelianGroup<Addition, u32>>::checked_inverse(&field, 0), Ok(0));
+
+        assert_eq!(Fp::<11>::ZERO.checked_inverse(), Err(Error::DivisionByZero));
+        assert_eq!(Fp::<11>::ZERO.checked_divide(Fp::new(1), Fp::ZERO), Err(Error::DivisionByZero));
+        assert_eq!(Fp::<11>::new(3).checked_inverse(), Ok(Fp::new(4)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot calculate inverse for zero")]
+    fn run_prime_field_divide_by_zero() {
+        PrimeField::new(11u32).divide(1, 0);
+    }
+
+    #[test]
+    fn run_fp() {
+        type F11 = Fp<11>;
+
+        assert_eq!(F11::new(3) + F11::new(10), F11::new(2));
+        assert_eq!(F11::new(1) - F11::new(3), F11::new(9));
+        assert_eq!(F11::new(2) * F11::new(10), F11::new(9));
+        assert_eq!(F11::new(10) / F11::new(2), F11::new(5));
+        assert_eq!(-F11::new(4), F11::new(7));
+        assert_eq!(-F11::ZERO, F11::ZERO);
+        assert_eq!(F11::new(25).value(), 3);
+        assert_eq!(F11::new(7).to_string(), "7");
+
+        let mut x = F11::new(5);
+        x += F11::new(9);
+        x *= F11::new(4);
+        x -= F11::new(3);
+        x /= F11::new(3);
+        assert_eq!(x, F11::new(3));
+
+        // every non zero element has an inverse
+        for i in 1..11 {
+            assert_eq!(F11::new(i) * F11::new(i).inverse(), F11::ONE);
+        }
+
+        // Fp acts as its own field structure
+        let field = F11::default();
+        assert_eq!(field.divide(F11::new(10), F11::new(2)), F11::new(5));
+        assert_eq!(<dyn AbelianGroup<Addition, F11>>::inverse(&field, F11::new(4)), F11::new(7));
+    }
+
+    #[test]
+    fn run_fp_large_modulus() {
+        // the largest prime below 2^64 matches the runtime PrimeField
+        const P: u64 = u64::MAX - 58;
+        let field = PrimeField::new(P);
+
+        let (a, b) = (u64::MAX - 100, 0x1234_5678_9abc_def0);
+        assert_eq!((Fp::<P>::new(a) * Fp::<P>::new(b)).value(), field.multiply(a, b));
+        assert_eq!((Fp::<P>::new(a) + Fp::<P>::new(b)).value(), field.add(a, b));
+        assert_eq!((Fp::<P>::new(b) - Fp::<P>::new(a)).value(), field.subtract(b, a));
+        assert_eq!((Fp::<P>::new(a) / Fp::<P>::new(b)).value(), field.divide(a, b));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot calculate inverse for zero")]
+    fn run_fp_divide_by_zero() {
+        let _ = Fp::<11>::new(1) / Fp::<11>::ZERO;
+    }
+
+    #[test]
+    fn run_prime_field_inverse() {
+        // the largest prime below 2^16 so every element of the field can be checked
+        let field = PrimeField::new(65521u32);
+
+        for e in 1..65521 {
+            let inverse = <dyn AbelianGroup<Multiplication, u32>>::inverse(&field, e);
+            assert_eq!(1, field.multiply(e, inverse));
+        }
+    }
+
 }
 
 
@@ -1,3 +1,5 @@
+use crate::math::integer::UnsignedInt;
+use crate::math::Error;
 
 /// Marks the Monoid and Group traits with a type
 pub trait Operation {}
@@ -15,22 +17,96 @@ pub trait Monoid<T: Operation, E> {
 
     /// Returns the identity element
     fn identity(&self) -> E;
+
+    /// Applies the operation to exponent copies of base using the square and multiply algorithm,
+    /// which needs O(log n) operations rather than n. The exponent can be any UnsignedInt so
+    /// exponents wider than the elements, such as a BigUint, are supported.
+    fn pow<X: UnsignedInt>(&self, base: E, exponent: &X) -> E
+    where
+        Self: Sized,
+        E: Clone,
+    {
+        let mut result = self.identity();
+        for i in (0..exponent.bits()).rev() {
+            result = self.apply(result.clone(), result);
+            if exponent.bit(i) {
+                result = self.apply(result, base.clone());
+            }
+        }
+        result
+    }
+
+    /// Exponentiation using the Montgomery ladder for secret exponents.
+    ///
+    /// Every one of the given number of bits costs exactly one operation and one squaring
+    /// whatever its value, so the sequence of operations does not reveal the exponent. Pass a
+    /// public bound such as the bit length of the group order rather than the bits of the
+    /// exponent itself. The operations and the branch choosing their operands are only as
+    /// constant time as the underlying element arithmetic.
+    ///
+    /// Bits of the exponent at or above the bound are ignored, which debug builds assert against.
+    fn pow_ct<X: UnsignedInt>(&self, base: E, exponent: &X, bits: usize) -> E
+    where
+        Self: Sized,
+        E: Clone,
+    {
+        debug_assert!(exponent.bits() <= bits, "the exponent has more than the given number of bits");
+        // invariant: r1 = r0 . base
+        let mut r0 = self.identity();
+        let mut r1 = base;
+        for i in (0..bits).rev() {
+            if exponent.bit(i) {
+                r0 = self.apply(r0, r1.clone());
+                r1 = self.apply(r1.clone(), r1);
+            } else {
+                r1 = self.apply(r0.clone(), r1);
+                r0 = self.apply(r0.clone(), r0);
+            }
+        }
+        r0
+    }
+}
+
+/// Additive notation for exponentiation where applying the operation n times to e is written
+/// as the scalar multiple n.e, as used for elliptic curve points.
+pub trait ScalarMul<E>: Monoid<Addition, E> {
+    /// Returns n.e using double and add
+    fn scalar_mul<X: UnsignedInt>(&self, e: E, n: &X) -> E
+    where
+        Self: Sized,
+        E: Clone,
+    {
+        <Self as Monoid<Addition, E>>::pow(self, e, n)
+    }
+
+    /// Returns n.e using the Montgomery ladder for secret scalars
+    fn scalar_mul_ct<X: UnsignedInt>(&self, e: E, n: &X, bits: usize) -> E
+    where
+        Self: Sized,
+        E: Clone,
+    {
+        <Self as Monoid<Addition, E>>::pow_ct(self, e, n, bits)
+    }
 }
 
+impl<S: Monoid<Addition, E>, E> ScalarMul<E> for S {}
+
 /// A group is a set and a binary operation (denoted by '.') which satisfies the following properties:
 /// Closure - a . b = c where a, b, and c are in the set
 /// Associativity - a . (b . c) = (a . b) . c
 /// Identity - a . I = a
 /// Inverse - a . a^-1 = I
-pub trait Group<T: Operation, E> {
-    /// The binary operation which takes elements e1 and e2 of type E and returns the result
-    fn apply(&self, e1: E, e2: E) -> E;
-
-    /// Returns the identity element
-    fn identity(&self) -> E;
-
+///
+/// Every group is a monoid so the operation and identity come from the Monoid supertrait.
+pub trait Group<T: Operation, E>: Monoid<T, E> {
     /// Returns the inverse of element e
     fn inverse(&self, e: E) -> E;
+
+    /// Returns the inverse of element e or an error where inverse would panic, such as for zero
+    /// under multiplication. Groups where every element can be inverted keep the default.
+    fn checked_inverse(&self, e: E) -> Result<E, Error> {
+        Ok(self.inverse(e))
+    }
 }
 
 /// A type which supports commutativity in addition to the standard group properties:
@@ -41,9 +117,10 @@ pub trait AbelianGroup<T: Operation, E>: Group<T, E> {}
 
 /// Integers under addition form a group where plus (+) is the group operation, zero is the identity
 /// and negation (-) can be used to get the inverse of an element
-struct AdditiveIntegers();
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdditiveIntegers();
 
-impl Group<Addition, i32> for AdditiveIntegers {
+impl Monoid<Addition, i32> for AdditiveIntegers {
     fn apply(&self, a: i32, b: i32) -> i32 {
         a + b
     }
@@ -51,7 +128,9 @@ impl Group<Addition, i32> for AdditiveIntegers {
     fn identity(&self) -> i32 {
         0
     }
+}
 
+impl Group<Addition, i32> for AdditiveIntegers {
     fn inverse(&self, a: i32) -> i32 {
         -a
     }
@@ -65,6 +144,9 @@ impl AbelianGroup<Addition, i32> for AdditiveIntegers {}
 mod tests {
 
     use super::*;
+    use crate::math::field::PrimeField;
+    use crate::math::ring::{NaturalNumbersModN, Ring};
+    use crate::math::BigUint;
 
     #[test]
     fn run_additive_integers() {
@@ -92,6 +174,63 @@ mod tests {
         assert_eq!(group.apply(a, b), group.apply(b, a));
     }
 
+    #[test]
+    fn run_scalar_mul() {
+        let group = AdditiveIntegers();
+
+        assert_eq!(group.scalar_mul(7, &5u32), 35);
+        assert_eq!(group.scalar_mul(-7, &5u32), -35);
+        assert_eq!(group.scalar_mul(7, &0u32), 0);
+        assert_eq!(group.scalar_mul_ct(7, &5u32, 32), 35);
+    }
+
+    #[test]
+    fn run_pow() {
+        let field = PrimeField::new(1_000_000_007u64);
+        let pow = |b: u64, e: u64| Monoid::<Multiplication, u64>::pow(&field, b, &e);
+
+        assert_eq!(pow(3, 0), 1);
+        assert_eq!(pow(3, 1), 3);
+        assert_eq!(pow(3, 13), 1_594_323);
+        assert_eq!(pow(2, 1_000_000), 2u64.pow_mod(&1_000_000, &1_000_000_007));
+
+        // Fermat's little theorem a^(p-1) = 1 and a^(p-2) is the inverse of a
+        assert_eq!(pow(123_456, 1_000_000_006), 1);
+        assert_eq!(field.multiply(123_456, pow(123_456, 1_000_000_005)), 1);
+
+        // the additive monoid of the same field gives scalar multiplication
+        assert_eq!(field.scalar_mul(123_456, &1_000u32), 123_456_000);
+
+        // exponents can be wider than the elements
+        let ring = NaturalNumbersModN::new(1_000u32);
+        let exponent = BigUint::one() << 200;
+        let expected = BigUint::from(7u32).pow_mod(&exponent, &BigUint::from(1_000u32));
+        assert_eq!(BigUint::from(Monoid::<Multiplication, u32>::pow(&ring, 7, &exponent)), expected);
+    }
+
+    #[test]
+    fn run_pow_ct() {
+        let field = PrimeField::new(1_000_000_007u64);
+
+        for exponent in [0u64, 1, 2, 3, 0xdead_beef, 1_000_000_006] {
+            let expected = Monoid::<Multiplication, u64>::pow(&field, 5, &exponent);
+            assert_eq!(Monoid::<Multiplication, u64>::pow_ct(&field, 5, &exponent, 64), expected);
+        }
+
+        // a bound larger than the exponent only adds leading zero bits
+        let exponent = BigUint::from_hex("1234567890abcdef1234567890abcdef").unwrap();
+        let expected = Monoid::<Multiplication, u64>::pow(&field, 5, &exponent);
+        assert_eq!(Monoid::<Multiplication, u64>::pow_ct(&field, 5, &exponent, 256), expected);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "the exponent has more than the given number of bits")]
+    fn run_pow_ct_with_too_few_bits() {
+        let field = PrimeField::new(1_000_000_007u64);
+        Monoid::<Multiplication, u64>::pow_ct(&field, 5, &0x1_0000u64, 16);
+    }
+
 }
 
 
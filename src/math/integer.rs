@@ -0,0 +1,250 @@
+use crate::math::BigUint;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::ops::{Add, Div, Mul, Rem, Shl, Shr, Sub};
+
+/// The unsigned integer types used for elements and moduli in the math module.
+///
+/// Algorithms such as Euclid's algorithm are written once against this trait so they work for
+/// the machine integer types and for BigUint alike.
+pub trait UnsignedInt:
+    Clone
+    + Eq
+    + Ord
+    + Hash
+    + Debug
+    + Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + Shl<usize, Output = Self>
+    + Shr<usize, Output = Self>
+{
+    fn zero() -> Self;
+
+    fn one() -> Self;
+
+    /// Converts from a u32 which fits in every supported type
+    fn from_u32(value: u32) -> Self;
+
+    /// Returns the value as a u64 if it fits
+    fn to_u64(&self) -> Option<u64>;
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+
+    fn is_one(&self) -> bool {
+        *self == Self::one()
+    }
+
+    fn is_even(&self) -> bool {
+        !self.bit(0)
+    }
+
+    /// Returns the number of bits required to represent the value
+    fn bits(&self) -> usize;
+
+    /// Returns the value of bit i where bit 0 is the least significant bit
+    fn bit(&self, i: usize) -> bool;
+
+    /// Returns the number of trailing zero bits, zero has none
+    fn trailing_zeros(&self) -> usize {
+        (0..self.bits()).find(|&i| self.bit(i)).unwrap_or(0)
+    }
+
+    /// Returns the quotient and remainder of dividing self by divisor
+    fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        (self.clone() / divisor.clone(), self.clone() % divisor.clone())
+    }
+
+    /// Returns (self + rhs) mod m without overflowing
+    fn add_mod(&self, rhs: &Self, m: &Self) -> Self {
+        let a = self.clone() % m.clone();
+        let b = rhs.clone() % m.clone();
+        // a + b >= m is checked as a >= m - b so the sum is never formed when it could overflow
+        let gap = m.clone() - b.clone();
+        if a >= gap {
+            a - gap
+        } else {
+            a + b
+        }
+    }
+
+    /// Returns (self - rhs) mod m without underflowing
+    fn sub_mod(&self, rhs: &Self, m: &Self) -> Self {
+        let a = self.clone() % m.clone();
+        let b = rhs.clone() % m.clone();
+        if a >= b {
+            a - b
+        } else {
+            m.clone() - (b - a)
+        }
+    }
+
+    /// Returns (self * rhs) mod m, the product is formed in a wider type so it cannot overflow
+    fn mul_mod(&self, rhs: &Self, m: &Self) -> Self;
+
+    /// Returns self * rhs or None if the product does not fit
+    fn checked_mul(&self, rhs: &Self) -> Option<Self>;
+
+    /// Returns self^exponent mod m using square and multiply
+    fn pow_mod(&self, exponent: &Self, m: &Self) -> Self {
+        let mut result = Self::one() % m.clone();
+        for i in (0..exponent.bits()).rev() {
+            result = result.mul_mod(&result, m);
+            if exponent.bit(i) {
+                result = result.mul_mod(self, m);
+            }
+        }
+        result
+    }
+}
+
+/// Implements UnsignedInt for a primitive type given an expression for the modular product
+macro_rules! impl_unsigned_int {
+    ($t:ty, |$a:ident, $b:ident, $m:ident| $mul_mod:expr) => {
+        impl UnsignedInt for $t {
+            fn zero() -> Self {
+                0
+            }
+
+            fn one() -> Self {
+                1
+            }
+
+            fn from_u32(value: u32) -> Self {
+                value as $t
+            }
+
+            fn to_u64(&self) -> Option<u64> {
+                u64::try_from(*self).ok()
+            }
+
+            fn bits(&self) -> usize {
+                (<$t>::BITS - self.leading_zeros()) as usize
+            }
+
+            fn bit(&self, i: usize) -> bool {
+                i < <$t>::BITS as usize && (self >> i) & 1 == 1
+            }
+
+            fn trailing_zeros(&self) -> usize {
+                if *self == 0 {
+                    0
+                } else {
+                    <$t>::trailing_zeros(*self) as usize
+                }
+            }
+
+            fn mul_mod(&self, rhs: &Self, m: &Self) -> Self {
+                let ($a, $b, $m) = (*self, *rhs, *m);
+                $mul_mod
+            }
+
+            fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+                <$t>::checked_mul(*self, *rhs)
+            }
+        }
+    };
+}
+
+impl_unsigned_int!(u32, |a, b, m| (a as u64 * b as u64 % m as u64) as u32);
+impl_unsigned_int!(u64, |a, b, m| (a as u128 * b as u128 % m as u128) as u64);
+// there is no primitive wider than u128 so the product is formed as a BigUint
+impl_unsigned_int!(u128, |a, b, m| (BigUint::from(a) * BigUint::from(b) % BigUint::from(m)).to_u128().unwrap());
+
+impl UnsignedInt for BigUint {
+    fn zero() -> Self {
+        BigUint::zero()
+    }
+
+    fn one() -> Self {
+        BigUint::one()
+    }
+
+    fn from_u32(value: u32) -> Self {
+        BigUint::from(value)
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        BigUint::to_u64(self)
+    }
+
+    fn is_zero(&self) -> bool {
+        BigUint::is_zero(self)
+    }
+
+    fn bits(&self) -> usize {
+        BigUint::bits(self)
+    }
+
+    fn bit(&self, i: usize) -> bool {
+        BigUint::bit(self, i)
+    }
+
+    fn trailing_zeros(&self) -> usize {
+        BigUint::trailing_zeros(self).unwrap_or(0)
+    }
+
+    fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        BigUint::div_rem(self, divisor)
+    }
+
+    fn mul_mod(&self, rhs: &Self, m: &Self) -> Self {
+        self * rhs % m
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        Some(self * rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Checks the modular operations against BigUint arithmetic
+    fn check_mod_ops<E: UnsignedInt + Into<BigUint> + Copy>(values: &[E], m: E) {
+        let big_m: BigUint = m.into();
+        for &a in values {
+            for &b in values {
+                let (big_a, big_b): (BigUint, BigUint) = (a.into(), b.into());
+                assert_eq!(a.add_mod(&b, &m).into(), (&big_a + &big_b) % &big_m);
+                assert_eq!(a.mul_mod(&b, &m).into(), (&big_a * &big_b) % &big_m);
+                let expected = (&big_a % &big_m + &big_m - &big_b % &big_m) % &big_m;
+                assert_eq!(a.sub_mod(&b, &m).into(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn run_mod_ops_near_the_top_of_each_width() {
+        let m = u32::MAX - 4;
+        check_mod_ops(&[0, 1, 2, m - 2, m - 1, m, m + 1, u32::MAX], m);
+
+        let m = u64::MAX - 58;
+        check_mod_ops(&[0, 1, 2, m - 2, m - 1, m, m + 1, u64::MAX], m);
+
+        let m = u128::MAX - 158;
+        check_mod_ops(&[0, 1, 2, m - 2, m - 1, m, m + 1, u128::MAX], m);
+    }
+
+    #[test]
+    fn run_pow_mod() {
+        assert_eq!(3u32.pow_mod(&4, &7), 4);
+        assert_eq!(5u64.pow_mod(&0, &7), 1);
+        assert_eq!(5u64.pow_mod(&3, &1), 0);
+
+        // Fermat's little theorem for the largest prime below 2^64
+        let p = u64::MAX - 58;
+        assert_eq!(0x1234_5678u64.pow_mod(&(p - 1), &p), 1);
+
+        let p = BigUint::from(p);
+        assert!(BigUint::from(7u32).pow_mod(&(&p - BigUint::one()), &p).is_one());
+    }
+
+}
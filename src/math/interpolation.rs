@@ -0,0 +1,282 @@
+use crate::math::field::Field;
+use crate::math::group::{Addition, Group, Monoid, Multiplication};
+use crate::math::polynomial::{Polynomial, PolynomialRing};
+use crate::math::ring::Ring;
+use crate::math::Error;
+
+/// Returns the barycentric weights w_i = 1 / Π_(j≠i) (x_i - x_j), or DivisionByZero if two of
+/// the x coordinates are equal
+pub fn barycentric_weights<F: Field<E>, E: Clone + PartialEq>(field: &F, xs: &[E]) -> Result<Vec<E>, Error> {
+    let one = <F as Monoid<Multiplication, E>>::identity(field);
+    xs.iter()
+        .enumerate()
+        .map(|(i, x_i)| {
+            let product = xs
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .fold(one.clone(), |acc, (_, x_j)| field.multiply(acc, field.subtract(x_i.clone(), x_j.clone())));
+            <F as Group<Multiplication, E>>::checked_inverse(field, product)
+        })
+        .collect()
+}
+
+/// Returns the polynomial of lowest degree through the given (x, y) points, or DivisionByZero if
+/// two points share an x coordinate.
+///
+/// The Lagrange basis polynomial for x_i is w_i l(x) / (x - x_i) where l(x) = Π (x - x_j) vanishes
+/// on every point, so it is one at x_i and zero at the others.
+pub fn lagrange<F: Field<E>, E: Clone + PartialEq>(field: &F, points: &[(E, E)]) -> Result<Polynomial<E>, Error> {
+    let (xs, ys): (Vec<E>, Vec<E>) = points.iter().cloned().unzip();
+    Barycentric::new(field, xs)?.interpolate(&ys)
+}
+
+/// Returns the value at zero of the polynomial of lowest degree through the given points without
+/// building it, which is how a Shamir secret is recovered from its shares:
+/// p(0) = Σ y_i Π_(j≠i) x_j / (x_j - x_i)
+pub fn evaluate_at_zero<F: Field<E>, E: Clone + PartialEq>(field: &F, points: &[(E, E)]) -> Result<E, Error> {
+    let zero = <F as Monoid<Addition, E>>::identity(field);
+    let one = <F as Monoid<Multiplication, E>>::identity(field);
+
+    let mut result = zero;
+    for (i, (x_i, y_i)) in points.iter().enumerate() {
+        let (numerator, denominator) = points.iter().enumerate().filter(|&(j, _)| j != i).fold(
+            (one.clone(), one.clone()),
+            |(n, d), (_, (x_j, _))| (field.multiply(n, x_j.clone()), field.multiply(d, field.subtract(x_j.clone(), x_i.clone()))),
+        );
+        let basis = field.checked_divide(numerator, denominator)?;
+        result = field.add(result, field.multiply(y_i.clone(), basis));
+    }
+    Ok(result)
+}
+
+/// Lagrange interpolation in barycentric form for repeated use on the same x coordinates.
+///
+/// The weights and the vanishing polynomial depend only on the x coordinates so they are computed
+/// once, after which each set of y values is evaluated in O(n) rather than O(n^2):
+/// p(x) = l(x) Σ w_i y_i / (x - x_i) where l(x) = Π (x - x_i)
+pub struct Barycentric<'a, F, E> {
+    field: &'a F,
+    xs: Vec<E>,
+    weights: Vec<E>,
+    vanishing: Polynomial<E>,
+}
+
+impl<'a, F: Field<E>, E: Clone + PartialEq> Barycentric<'a, F, E> {
+    /// Returns DivisionByZero if two of the x coordinates are equal
+    pub fn new(field: &'a F, xs: Vec<E>) -> Result<Self, Error> {
+        let weights = barycentric_weights(field, &xs)?;
+        let vanishing = vanishing_polynomial(field, &xs);
+        Ok(Barycentric { field, xs, weights, vanishing })
+    }
+
+    pub fn xs(&self) -> &[E] {
+        &self.xs
+    }
+
+    pub fn weights(&self) -> &[E] {
+        &self.weights
+    }
+
+    /// Evaluates at x the polynomial taking the value ys[i] at xs[i], or returns LengthMismatch if
+    /// there is not one y value for each x coordinate
+    pub fn evaluate(&self, ys: &[E], x: &E) -> Result<E, Error> {
+        if ys.len() != self.xs.len() {
+            return Err(Error::LengthMismatch);
+        }
+        let field = self.field;
+
+        // the formula divides by zero at the nodes themselves
+        if let Some(i) = self.xs.iter().position(|x_i| x_i == x) {
+            return Ok(ys[i].clone());
+        }
+
+        let mut l = <F as Monoid<Multiplication, E>>::identity(field);
+        let mut sum = <F as Monoid<Addition, E>>::identity(field);
+        for ((x_i, w_i), y_i) in self.xs.iter().zip(&self.weights).zip(ys) {
+            let difference = field.subtract(x.clone(), x_i.clone());
+            l = field.multiply(l, difference.clone());
+            sum = field.add(sum, field.divide(field.multiply(w_i.clone(), y_i.clone()), difference));
+        }
+        Ok(field.multiply(l, sum))
+    }
+
+    /// Returns the polynomial taking the value ys[i] at xs[i], or LengthMismatch if there is not
+    /// one y value for each x coordinate. Each basis polynomial w_i l(x) / (x - x_i) divides the
+    /// cached l by a linear factor, which takes O(n) steps.
+    pub fn interpolate(&self, ys: &[E]) -> Result<Polynomial<E>, Error> {
+        if ys.len() != self.xs.len() {
+            return Err(Error::LengthMismatch);
+        }
+        let field = self.field;
+        let ring = PolynomialRing::new(field);
+
+        let mut result = Monoid::<Addition, _>::identity(&ring);
+        for ((x_i, w_i), y_i) in self.xs.iter().zip(&self.weights).zip(ys) {
+            let (basis, _) = ring.div_rem(&self.vanishing, &linear(field, x_i))?;
+            let scaled = Ring::multiply(&ring, basis, ring.constant(field.multiply(y_i.clone(), w_i.clone())));
+            result = Ring::add(&ring, result, scaled);
+        }
+        Ok(result)
+    }
+}
+
+/// A binary tree of products of the linear factors (x - x_i). The leaves are the factors and
+/// each node is the product of its two children so the root vanishes on every point.
+pub struct SubproductTree<E> {
+    /// Level zero holds the leaves and the last level holds the root
+    levels: Vec<Vec<Polynomial<E>>>,
+}
+
+impl<E: Clone + PartialEq> SubproductTree<E> {
+    pub fn new<F: Field<E>>(field: &F, xs: &[E]) -> Self {
+        let ring = PolynomialRing::new(field);
+        let mut levels = vec![xs.iter().map(|x| linear(field, x)).collect::<Vec<_>>()];
+        while levels.last().unwrap().len() > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => Ring::multiply(&ring, a.clone(), b.clone()),
+                    [a] => a.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        SubproductTree { levels }
+    }
+
+    /// Returns the product of every linear factor, or None if there are no points
+    pub fn root(&self) -> Option<&Polynomial<E>> {
+        self.levels.last().and_then(|level| level.first())
+    }
+
+    /// Evaluates p at every point by reducing it mod each node on the way down the tree.
+    ///
+    /// The remainder mod a node agrees with p on that node's points and has lower degree, so at
+    /// the leaves p mod (x - x_i) is the constant p(x_i).
+    pub fn evaluate<F: Field<E>>(&self, field: &F, p: &Polynomial<E>) -> Vec<E> {
+        let ring = PolynomialRing::new(field);
+        let Some(root) = self.root() else {
+            return vec![];
+        };
+
+        let mut remainders = vec![ring.div_rem(p, root).unwrap().1];
+        for level in self.levels.iter().rev().skip(1) {
+            remainders = level
+                .iter()
+                .enumerate()
+                .map(|(i, node)| ring.div_rem(&remainders[i / 2], node).unwrap().1)
+                .collect();
+        }
+
+        let zero = <F as Monoid<Addition, E>>::identity(field);
+        remainders.into_iter().map(|r| r.coefficient(0).cloned().unwrap_or(zero.clone())).collect()
+    }
+}
+
+/// Evaluates p at every one of xs using a subproduct tree
+pub fn multipoint_evaluate<F: Field<E>, E: Clone + PartialEq>(field: &F, p: &Polynomial<E>, xs: &[E]) -> Vec<E> {
+    SubproductTree::new(field, xs).evaluate(field, p)
+}
+
+/// Returns x - a
+fn linear<F: Field<E>, E: Clone + PartialEq>(field: &F, a: &E) -> Polynomial<E> {
+    let one = <F as Monoid<Multiplication, E>>::identity(field);
+    PolynomialRing::new(field).polynomial(vec![<F as Group<Addition, E>>::inverse(field, a.clone()), one])
+}
+
+/// Returns Π (x - x_i)
+fn vanishing_polynomial<F: Field<E>, E: Clone + PartialEq>(field: &F, xs: &[E]) -> Polynomial<E> {
+    let ring = PolynomialRing::new(field);
+    xs.iter().fold(Monoid::<Multiplication, _>::identity(&ring), |acc, x| Ring::multiply(&ring, acc, linear(field, x)))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::math::field::{Fp, PrimeField};
+    use crate::math::primes::random_below;
+    use ring::rand::SystemRandom;
+
+    #[test]
+    fn run_lagrange() {
+        let field = PrimeField::new(97u32);
+        let ring = PolynomialRing::new(&field);
+
+        // the points of 2x^2 + 3x + 5
+        let p = ring.polynomial(vec![5, 3, 2]);
+        let points: Vec<(u32, u32)> = [1, 4, 9].iter().map(|&x| (x, ring.evaluate(&p, &x))).collect();
+        assert_eq!(lagrange(&field, &points), Ok(p.clone()));
+        assert_eq!(evaluate_at_zero(&field, &points), Ok(5));
+
+        // more points than needed still give the same polynomial
+        let points: Vec<(u32, u32)> = (10..20).map(|x| (x, ring.evaluate(&p, &x))).collect();
+        assert_eq!(lagrange(&field, &points), Ok(p));
+
+        assert_eq!(lagrange(&field, &[(1, 2), (1, 3)]), Err(Error::DivisionByZero));
+        assert_eq!(evaluate_at_zero(&field, &[(1, 2), (1, 3)]), Err(Error::DivisionByZero));
+        assert_eq!(lagrange(&field, &[]), Ok(ring.polynomial(vec![])));
+    }
+
+    #[test]
+    fn run_barycentric() {
+        type F = Fp<65537>;
+        let xs: Vec<F> = (1..=8).map(F::new).collect();
+        let barycentric = Barycentric::new(&F::ZERO, xs.clone()).unwrap();
+        let ring = PolynomialRing::new(&F::ZERO);
+
+        // the same weights serve every set of y values
+        for seed in 1..5u64 {
+            let ys: Vec<F> = (0..8).map(|i| F::new(seed * 1000 + i * i * seed)).collect();
+            let p = barycentric.interpolate(&ys).unwrap();
+            for x in (0..50).map(F::new) {
+                assert_eq!(barycentric.evaluate(&ys, &x), Ok(ring.evaluate(&p, &x)));
+            }
+        }
+        assert_eq!(barycentric.evaluate(&xs[1..], &F::ONE), Err(Error::LengthMismatch));
+        assert_eq!(barycentric.interpolate(&xs[1..]).err(), Some(Error::LengthMismatch));
+        assert_eq!(Barycentric::new(&F::ZERO, vec![F::ONE, F::ONE]).err(), Some(Error::DivisionByZero));
+    }
+
+    #[test]
+    fn run_multipoint_evaluation() {
+        let field = PrimeField::new(998_244_353u64);
+        let ring = PolynomialRing::new(&field);
+        let p = ring.polynomial((0..40).map(|i| i * i * 7919 + 3).collect());
+
+        for n in [0usize, 1, 2, 3, 7, 64, 100] {
+            let xs: Vec<u64> = (0..n as u64).map(|x| x * 31 + 2).collect();
+            let expected: Vec<u64> = xs.iter().map(|x| ring.evaluate(&p, x)).collect();
+            assert_eq!(multipoint_evaluate(&field, &p, &xs), expected, "n = {}", n);
+        }
+
+        let tree = SubproductTree::new(&field, &[1, 2, 3]);
+        assert_eq!(tree.root(), Some(&ring.polynomial(vec![998_244_353 - 6, 11, 998_244_353 - 6, 1])));
+    }
+
+    #[test]
+    fn run_shamir_secret_sharing() {
+        let rng = SystemRandom::new();
+        let p = u64::MAX - 58;
+        let field = PrimeField::new(p);
+        let ring = PolynomialRing::new(&field);
+
+        // a random polynomial of degree 2 hides the secret so any 3 of 5 shares recover it
+        let secret = random_below(&rng, &p);
+        let polynomial = ring.polynomial(vec![secret, random_below(&rng, &p), random_below(&rng, &p)]);
+        let shares: Vec<(u64, u64)> = (1..=5).map(|x| (x, ring.evaluate(&polynomial, &x))).collect();
+
+        for subset in [[0, 1, 2], [0, 2, 4], [1, 3, 4], [2, 3, 4]] {
+            let chosen: Vec<(u64, u64)> = subset.iter().map(|&i| shares[i]).collect();
+            assert_eq!(evaluate_at_zero(&field, &chosen), Ok(secret));
+        }
+
+        // two shares determine only a line which misses the secret
+        assert_ne!(evaluate_at_zero(&field, &shares[..2]), Ok(secret));
+    }
+
+}
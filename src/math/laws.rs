@@ -0,0 +1,306 @@
+use crate::math::field::Field;
+use crate::math::group::{AbelianGroup, Addition, Group, Monoid, Multiplication, Operation};
+use crate::math::ring::Ring;
+use std::fmt::Debug;
+
+/// The first law found not to hold along with the elements which demonstrate it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counterexample<E> {
+    pub law: &'static str,
+    pub elements: Vec<E>,
+}
+
+/// The elements the laws are checked against
+pub enum Elements<'a, E> {
+    /// Every element, pair and triple drawn from the slice is checked which suits small finite structures
+    All(&'a [E]),
+    /// The given number of elements, pairs and triples are drawn at random from the generator
+    /// which suits structures too large to check exhaustively
+    Sampled(&'a mut dyn FnMut() -> E, usize),
+}
+
+impl<E: Clone> Elements<'_, E> {
+    fn for_each_single<F>(&mut self, mut f: F) -> Result<(), Counterexample<E>>
+    where
+        F: FnMut(&E) -> Result<(), Counterexample<E>>,
+    {
+        match self {
+            Elements::All(elements) => elements.iter().try_for_each(f),
+            Elements::Sampled(generator, samples) => (0..*samples).try_for_each(|_| f(&generator())),
+        }
+    }
+
+    fn for_each_triple<F>(&mut self, mut f: F) -> Result<(), Counterexample<E>>
+    where
+        F: FnMut(&E, &E, &E) -> Result<(), Counterexample<E>>,
+    {
+        match self {
+            Elements::All(elements) => {
+                for a in elements.iter() {
+                    for b in elements.iter() {
+                        for c in elements.iter() {
+                            f(a, b, c)?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+            Elements::Sampled(generator, samples) => {
+                (0..*samples).try_for_each(|_| f(&generator(), &generator(), &generator()))
+            }
+        }
+    }
+}
+
+/// Returns a counterexample for the law unless lhs equals rhs
+fn law<E: Clone + PartialEq>(name: &'static str, lhs: E, rhs: E, elements: &[&E]) -> Result<(), Counterexample<E>> {
+    if lhs == rhs {
+        Ok(())
+    } else {
+        Err(Counterexample { law: name, elements: elements.iter().map(|&e| e.clone()).collect() })
+    }
+}
+
+/// Checks associativity and identity
+pub fn check_monoid<T, E, S>(s: &S, elements: &mut Elements<'_, E>) -> Result<(), Counterexample<E>>
+where
+    T: Operation,
+    E: Clone + PartialEq + Debug,
+    S: Monoid<T, E> + ?Sized,
+{
+    elements.for_each_single(|a| {
+        law("left identity", s.apply(s.identity(), a.clone()), a.clone(), &[a])?;
+        law("right identity", s.apply(a.clone(), s.identity()), a.clone(), &[a])
+    })?;
+
+    check_closure(elements, |a, b| s.apply(a.clone(), b.clone()))?;
+
+    elements.for_each_triple(|a, b, c| {
+        let lhs = s.apply(a.clone(), s.apply(b.clone(), c.clone()));
+        let rhs = s.apply(s.apply(a.clone(), b.clone()), c.clone());
+        law("associativity", lhs, rhs, &[a, b, c])
+    })
+}
+
+/// Checks every result of the operation is one of the elements. This needs every element to
+/// be known so it is skipped when the elements are sampled.
+fn check_closure<E, F>(elements: &Elements<'_, E>, op: F) -> Result<(), Counterexample<E>>
+where
+    E: Clone + PartialEq,
+    F: Fn(&E, &E) -> E,
+{
+    if let Elements::All(all) = elements {
+        for a in all.iter() {
+            for b in all.iter() {
+                if !all.contains(&op(a, b)) {
+                    return Err(Counterexample { law: "closure", elements: vec![a.clone(), b.clone()] });
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks the monoid laws and that every element has an inverse
+pub fn check_group<T, E, S>(s: &S, elements: &mut Elements<'_, E>) -> Result<(), Counterexample<E>>
+where
+    T: Operation,
+    E: Clone + PartialEq + Debug,
+    S: Group<T, E> + ?Sized,
+{
+    check_monoid::<T, E, S>(s, elements)?;
+    check_closure(elements, |a, _| s.inverse(a.clone()))?;
+
+    elements.for_each_single(|a| {
+        law("left inverse", s.apply(s.inverse(a.clone()), a.clone()), s.identity(), &[a])?;
+        law("right inverse", s.apply(a.clone(), s.inverse(a.clone())), s.identity(), &[a])
+    })
+}
+
+/// Checks the group laws and commutativity
+pub fn check_abelian_group<T, E, S>(s: &S, elements: &mut Elements<'_, E>) -> Result<(), Counterexample<E>>
+where
+    T: Operation,
+    E: Clone + PartialEq + Debug,
+    S: AbelianGroup<T, E> + ?Sized,
+{
+    check_group::<T, E, S>(s, elements)?;
+
+    elements.for_each_triple(|a, b, _| {
+        law("commutativity", s.apply(a.clone(), b.clone()), s.apply(b.clone(), a.clone()), &[a, b])
+    })
+}
+
+/// Checks addition forms an abelian group, multiplication forms a monoid and that
+/// multiplication distributes over addition from both sides
+pub fn check_ring<E, S>(s: &S, elements: &mut Elements<'_, E>) -> Result<(), Counterexample<E>>
+where
+    E: Clone + PartialEq + Debug,
+    S: Ring<E> + ?Sized,
+{
+    check_abelian_group::<Addition, E, S>(s, elements)?;
+    check_monoid::<Multiplication, E, S>(s, elements)?;
+
+    elements.for_each_triple(|a, b, c| {
+        let lhs = s.multiply(a.clone(), s.add(b.clone(), c.clone()));
+        let rhs = s.add(s.multiply(a.clone(), b.clone()), s.multiply(a.clone(), c.clone()));
+        law("left distributivity", lhs, rhs, &[a, b, c])?;
+
+        let lhs = s.multiply(s.add(b.clone(), c.clone()), a.clone());
+        let rhs = s.add(s.multiply(b.clone(), a.clone()), s.multiply(c.clone(), a.clone()));
+        law("right distributivity", lhs, rhs, &[a, b, c])
+    })
+}
+
+/// Checks the ring laws, that multiplication commutes, that zero and one differ and that
+/// every non-zero element has a multiplicative inverse
+pub fn check_field<E, S>(s: &S, elements: &mut Elements<'_, E>) -> Result<(), Counterexample<E>>
+where
+    E: Clone + PartialEq + Debug,
+    S: Field<E> + ?Sized,
+{
+    check_ring::<E, S>(s, elements)?;
+
+    let zero = <S as Monoid<Addition, E>>::identity(s);
+    let one = <S as Monoid<Multiplication, E>>::identity(s);
+    if zero == one {
+        return Err(Counterexample { law: "zero is not one", elements: vec![zero] });
+    }
+
+    elements.for_each_triple(|a, b, _| {
+        law("multiplicative commutativity", s.multiply(a.clone(), b.clone()), s.multiply(b.clone(), a.clone()), &[a, b])
+    })?;
+
+    elements.for_each_single(|a| {
+        if *a == zero {
+            return Ok(());
+        }
+        let inverse = <S as Group<Multiplication, E>>::inverse(s, a.clone());
+        law("multiplicative inverse", s.multiply(a.clone(), inverse), one.clone(), &[a])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::math::field::{Fp, PrimeField};
+    use crate::math::group::AdditiveIntegers;
+    use crate::math::ring::NaturalNumbersModN;
+    use crate::math::BigUint;
+    use ring::rand::{SecureRandom, SystemRandom};
+
+    fn random_u64(rng: &SystemRandom) -> u64 {
+        let mut bytes = [0u8; 8];
+        rng.fill(&mut bytes).unwrap();
+        u64::from_be_bytes(bytes)
+    }
+
+    /// Reproduces the original u32 PrimeField arithmetic which overflowed for large moduli and
+    /// returned p rather than zero for the additive inverse of zero
+    struct OverflowingPrimeField(u32);
+
+    impl Monoid<Addition, u32> for OverflowingPrimeField {
+        fn apply(&self, e1: u32, e2: u32) -> u32 {
+            e1.wrapping_add(e2) % self.0
+        }
+
+        fn identity(&self) -> u32 {
+            0
+        }
+    }
+
+    impl Group<Addition, u32> for OverflowingPrimeField {
+        fn inverse(&self, e: u32) -> u32 {
+            self.0 - e % self.0
+        }
+    }
+
+    impl AbelianGroup<Addition, u32> for OverflowingPrimeField {}
+
+    impl Monoid<Multiplication, u32> for OverflowingPrimeField {
+        fn apply(&self, e1: u32, e2: u32) -> u32 {
+            e1.wrapping_mul(e2) % self.0
+        }
+
+        fn identity(&self) -> u32 {
+            1
+        }
+    }
+
+    impl Group<Multiplication, u32> for OverflowingPrimeField {
+        fn inverse(&self, e: u32) -> u32 {
+            crate::math::euclid::mod_inverse(e, self.0).unwrap()
+        }
+    }
+
+    impl AbelianGroup<Multiplication, u32> for OverflowingPrimeField {}
+    impl Ring<u32> for OverflowingPrimeField {}
+    impl Field<u32> for OverflowingPrimeField {}
+
+    #[test]
+    fn run_small_structures_exhaustively() {
+        let elements: Vec<u32> = (0..13).collect();
+        assert_eq!(check_field(&PrimeField::new(13u32), &mut Elements::All(&elements)), Ok(()));
+
+        let elements: Vec<u32> = (0..12).collect();
+        assert_eq!(check_ring(&NaturalNumbersModN::new(12u32), &mut Elements::All(&elements)), Ok(()));
+
+        let elements: Vec<Fp<7>> = (0..7).map(Fp::new).collect();
+        assert_eq!(check_field(&Fp::<7>::ZERO, &mut Elements::All(&elements)), Ok(()));
+
+    }
+
+    #[test]
+    fn run_infinite_group_by_sampling() {
+        // the integers are not closed over any finite subset so they can only be sampled
+        let rng = SystemRandom::new();
+        let mut generator = || (random_u64(&rng) as i16) as i32;
+        let group = AdditiveIntegers();
+        assert_eq!(check_abelian_group::<Addition, _, _>(&group, &mut Elements::Sampled(&mut generator, 1000)), Ok(()));
+    }
+
+    #[test]
+    fn run_large_structures_by_sampling() {
+        let rng = SystemRandom::new();
+
+        let p = u64::MAX - 58;
+        let mut generator = || random_u64(&rng) % p;
+        assert_eq!(check_field(&PrimeField::new(p), &mut Elements::Sampled(&mut generator, 200)), Ok(()));
+
+        let mut generator = || random_u64(&rng);
+        assert_eq!(check_ring(&NaturalNumbersModN::new(u64::MAX), &mut Elements::Sampled(&mut generator, 200)), Ok(()));
+
+        let p = BigUint::from_hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff").unwrap();
+        let field = PrimeField::new(p.clone());
+        let mut generator = || {
+            let mut bytes = [0u8; 32];
+            rng.fill(&mut bytes).unwrap();
+            BigUint::from_bytes_be(&bytes) % &p
+        };
+        assert_eq!(check_field(&field, &mut Elements::Sampled(&mut generator, 20)), Ok(()));
+    }
+
+    #[test]
+    fn run_finds_the_original_prime_field_bugs() {
+        // a small modulus never overflows but zero still has the wrong additive inverse
+        let elements: Vec<u32> = (0..11).collect();
+        let result = check_field(&OverflowingPrimeField(11), &mut Elements::All(&elements));
+        assert_eq!(result, Err(Counterexample { law: "closure", elements: vec![0, 0] }));
+
+        // with the largest prime below 2^32 products wrap so the arithmetic is really mod 2^32,
+        // the ring laws still hold but the inverses no longer multiply back to one
+        let rng = SystemRandom::new();
+        let p = u32::MAX - 4;
+        let mut generator = || random_u64(&rng) as u32 % p;
+        let result = check_field(&OverflowingPrimeField(p), &mut Elements::Sampled(&mut generator, 100));
+        let counterexample = result.unwrap_err();
+        assert_eq!(counterexample.law, "multiplicative inverse");
+
+        // the reported element really has a wrapping product with its inverse other than one
+        let field = OverflowingPrimeField(p);
+        let [e] = counterexample.elements[..] else { panic!("expected a single element") };
+        assert_ne!(field.multiply(e, Group::<Multiplication, u32>::inverse(&field, e)), 1);
+    }
+
+}
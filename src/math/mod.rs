@@ -11,13 +11,32 @@
 // Fermat's Last Theorem
 // chinese remainder theorem
 
-mod group;
-mod ring;
-mod field;
-// cyclic groups
-// polynomials
-// polynomials - fast fourier transform
-// elliptic curves
-
-
+pub mod group;
+pub mod ring;
+pub mod field;
+pub mod biguint;
+pub mod error;
+pub mod integer;
+pub mod euclid;
+pub mod elem;
+pub mod laws;
+pub mod number_theory;
+pub mod crt;
+pub mod primes;
+pub mod prime_gen;
+pub mod factor;
+pub mod ecm;
+pub mod cyclic;
+pub mod units;
+pub mod dlog;
+pub mod roots;
+pub mod polynomial;
+pub mod interpolation;
+pub mod ntt;
+pub mod elliptic_curve;
+pub mod sec1;
+pub mod named_curves;
+pub mod point_counting;
 
+pub use biguint::BigUint;
+pub use error::Error;
@@ -0,0 +1,114 @@
+use crate::math::elliptic_curve::{EllipticCurve, Point};
+use crate::math::field::PrimeField;
+use crate::math::BigUint;
+
+/// The domain parameters of a named curve y^2 = x^3 + ax + b mod p as hex strings, with the
+/// generator G, its prime order n and the cofactor h = #E / n
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurveParams {
+    pub name: &'static str,
+    pub p: &'static str,
+    pub a: &'static str,
+    pub b: &'static str,
+    pub gx: &'static str,
+    pub gy: &'static str,
+    pub n: &'static str,
+    pub h: u32,
+}
+
+/// NIST P-256, also known as secp256r1 and prime256v1
+pub const P256: CurveParams = CurveParams {
+    name: "P-256",
+    p: "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff",
+    a: "ffffffff00000001000000000000000000000000fffffffffffffffffffffffc",
+    b: "5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b",
+    gx: "6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+    gy: "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5",
+    n: "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551",
+    h: 1,
+};
+
+/// NIST P-384, also known as secp384r1
+pub const P384: CurveParams = CurveParams {
+    name: "P-384",
+    p: "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffff0000000000000000ffffffff",
+    a: "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffff0000000000000000fffffffc",
+    b: "b3312fa7e23ee7e4988e056be3f82d19181d9c6efe8141120314088f5013875ac656398d8a2ed19d2a85c8edd3ec2aef",
+    gx: "aa87ca22be8b05378eb1c71ef320ad746e1d3b628ba79b9859f741e082542a385502f25dbf55296c3a545e3872760ab7",
+    gy: "3617de4a96262c6f5d9e98bf9292dc29f8f41dbd289a147ce9da3113b5f0b8c00a60b1ce1d7e819d7a431d7c90ea0e5f",
+    n: "ffffffffffffffffffffffffffffffffffffffffffffffffc7634d81f4372ddf581a0db248b0a77aecec196accc52973",
+    h: 1,
+};
+
+/// The Koblitz curve y^2 = x^3 + 7 used by Bitcoin
+pub const SECP256K1: CurveParams = CurveParams {
+    name: "secp256k1",
+    p: "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+    a: "0",
+    b: "7",
+    gx: "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+    gy: "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+    n: "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+    h: 1,
+};
+
+impl CurveParams {
+    pub fn curve(&self) -> EllipticCurve<PrimeField<BigUint>, BigUint> {
+        EllipticCurve::new(PrimeField::new(parse(self.p)), parse(self.a), parse(self.b))
+    }
+
+    pub fn generator(&self) -> Point<BigUint> {
+        Point::Affine { x: parse(self.gx), y: parse(self.gy) }
+    }
+
+    pub fn order(&self) -> BigUint {
+        parse(self.n)
+    }
+}
+
+/// The constants are known to be valid hex
+fn parse(s: &str) -> BigUint {
+    BigUint::from_hex(s).expect("curve parameters are hex")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::math::group::{Addition, Group, Monoid};
+    use crate::math::primes::is_prime;
+    use crate::math::sec1::{encode_point_hex, Encoding};
+
+    #[test]
+    fn run_generators_have_the_stated_order() {
+        for params in [P256, P384, SECP256K1] {
+            let curve = params.curve();
+            let g = params.generator();
+            let n = params.order();
+            assert!(curve.is_on_curve(&g), "{}", params.name);
+            assert!(is_prime(&n), "{}", params.name);
+            assert_eq!(params.h, 1);
+
+            // n.G is the identity and (n - 1).G is the inverse of G
+            assert_eq!(curve.multiply(&g, &n), Point::Infinity, "{}", params.name);
+            assert_eq!(curve.multiply(&g, &(n - BigUint::one())), curve.inverse(g.clone()), "{}", params.name);
+        }
+    }
+
+    #[test]
+    fn run_known_multiples() {
+        // 2G on secp256k1
+        let curve = SECP256K1.curve();
+        let two_g = curve.multiply(&SECP256K1.generator(), &BigUint::from(2u32));
+        assert_eq!(two_g.x().unwrap().to_hex(), "c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5");
+        assert_eq!(two_g, Monoid::<Addition, _>::apply(&curve, SECP256K1.generator(), SECP256K1.generator()));
+
+        // the compressed P-256 generator
+        let curve = P256.curve();
+        assert_eq!(
+            encode_point_hex(&curve, &P256.generator(), Encoding::Compressed),
+            "036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296"
+        );
+    }
+
+}
@@ -0,0 +1,263 @@
+use crate::math::field::{Field, PrimeField};
+use crate::math::group::{Monoid, Multiplication};
+use crate::math::integer::UnsignedInt;
+use crate::math::ring::Ring;
+use crate::math::Error;
+
+/// The shorter input length from which PrimeField convolutions use the transform rather than
+/// the schoolbook method, found with the ignored benchmark below
+pub const THRESHOLD: usize = 64;
+
+/// Returns an element of order exactly n, which must be a power of two, or None if n does not
+/// divide p - 1.
+///
+/// For any c the power c^((p - 1) / n) has order dividing n, and since n is a power of two the
+/// order is exactly n unless its n/2 power is already one. Half of the elements are quadratic
+/// non-residues which always pass, so the search is short.
+pub fn root_of_unity<E: UnsignedInt>(field: &PrimeField<E>, n: usize) -> Option<E> {
+    assert!(n.is_power_of_two(), "the order must be a power of two");
+    let one = E::one();
+    let p_minus_one = field.modulus().clone() - one.clone();
+    let log = n.trailing_zeros() as usize;
+    if log > p_minus_one.trailing_zeros() {
+        return None;
+    }
+    if n == 1 {
+        return Some(one);
+    }
+
+    let cofactor = p_minus_one >> log;
+    let half = one.clone() << (log - 1);
+    (2..).map(E::from_u32).find_map(|c| {
+        let w = Monoid::<Multiplication, E>::pow(field, c, &cofactor);
+        (Monoid::<Multiplication, E>::pow(field, w.clone(), &half) != one).then_some(w)
+    })
+}
+
+/// The number theoretic transform, the discrete Fourier transform over a prime field.
+///
+/// The transform of a_0, ..., a_(n-1) is the polynomial a_0 + a_1 x + ... evaluated at the powers
+/// of an n-th root of unity w. Polynomials multiply pointwise in that form so a product costs
+/// two forward transforms and an inverse, each O(n log n) with the Cooley-Tukey butterfly.
+pub struct Ntt<'a, E> {
+    field: &'a PrimeField<E>,
+    root: E,
+    /// w^0, ..., w^(n/2 - 1)
+    roots: Vec<E>,
+    /// w^0, ..., w^-(n/2 - 1)
+    inverse_roots: Vec<E>,
+    /// 1 / n which scales the inverse transform
+    size_inverse: E,
+}
+
+impl<'a, E: UnsignedInt> Ntt<'a, E> {
+    /// Returns NoRootOfUnity if size, which must be a power of two, does not divide p - 1
+    pub fn new(field: &'a PrimeField<E>, size: usize) -> Result<Self, Error> {
+        let root = root_of_unity(field, size).ok_or(Error::NoRootOfUnity)?;
+        let root_inverse = field.divide(E::one(), root.clone());
+        let powers = |w: E| {
+            std::iter::successors(Some(E::one()), move |x: &E| Some(field.multiply(x.clone(), w.clone())))
+                .take(size / 2)
+                .collect()
+        };
+        let size_inverse = field.divide(E::one(), (E::one() << size.trailing_zeros() as usize) % field.modulus().clone());
+
+        Ok(Ntt { field, roots: powers(root.clone()), inverse_roots: powers(root_inverse), root, size_inverse })
+    }
+
+    pub fn size(&self) -> usize {
+        (self.roots.len() * 2).max(1)
+    }
+
+    /// Returns the n-th root of unity the transform evaluates at
+    pub fn root(&self) -> &E {
+        &self.root
+    }
+
+    /// Replaces the coefficients a with their values at w^0, ..., w^(n-1)
+    pub fn forward(&self, a: &mut [E]) {
+        self.transform(a, &self.roots);
+    }
+
+    /// Replaces the values a at w^0, ..., w^(n-1) with the coefficients they came from
+    pub fn inverse(&self, a: &mut [E]) {
+        self.transform(a, &self.inverse_roots);
+        for x in a.iter_mut() {
+            *x = self.field.multiply(x.clone(), self.size_inverse.clone());
+        }
+    }
+
+    /// The iterative radix two butterfly over the bit reversed input
+    fn transform(&self, a: &mut [E], roots: &[E]) {
+        let n = a.len();
+        assert_eq!(n, self.size(), "the input length must match the transform size");
+        bit_reverse(a);
+
+        let mut len = 2;
+        while len <= n {
+            let (half, step) = (len / 2, n / len);
+            for start in (0..n).step_by(len) {
+                for k in 0..half {
+                    let u = a[start + k].clone();
+                    let v = self.field.multiply(a[start + k + half].clone(), roots[k * step].clone());
+                    a[start + k] = self.field.add(u.clone(), v.clone());
+                    a[start + k + half] = self.field.subtract(u, v);
+                }
+            }
+            len *= 2;
+        }
+    }
+}
+
+/// Returns the coefficients of the product of two polynomials by multiplying their transforms, or
+/// NoRootOfUnity if the field has no root of unity of order the next power of two above the
+/// product's length
+pub fn convolve<E: UnsignedInt>(field: &PrimeField<E>, a: &[E], b: &[E]) -> Result<Vec<E>, Error> {
+    if a.is_empty() || b.is_empty() {
+        return Ok(vec![]);
+    }
+    let length = a.len() + b.len() - 1;
+    let ntt = Ntt::new(field, length.next_power_of_two())?;
+
+    let padded = |x: &[E]| {
+        let mut x = x.to_vec();
+        x.resize(ntt.size(), E::zero());
+        ntt.forward(&mut x);
+        x
+    };
+    let (fa, fb) = (padded(a), padded(b));
+    let mut c: Vec<E> = fa.into_iter().zip(fb).map(|(x, y)| field.multiply(x, y)).collect();
+    ntt.inverse(&mut c);
+    c.truncate(length);
+    Ok(c)
+}
+
+/// Swaps each element with the one at the bit reversed index
+fn bit_reverse<E>(a: &mut [E]) {
+    let n = a.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::math::polynomial::PolynomialRing;
+    use crate::math::ring::schoolbook_convolve;
+    use std::time::Instant;
+
+    /// 119 * 2^23 + 1
+    const P998244353: u64 = 998_244_353;
+    /// The Goldilocks prime 2^64 - 2^32 + 1
+    const GOLDILOCKS: u64 = 0xffff_ffff_0000_0001;
+
+    fn pseudo_random(n: usize, seed: u64, p: u64) -> Vec<u64> {
+        (0..n as u64).map(|i| (i.wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ seed) % p).collect()
+    }
+
+    #[test]
+    fn run_root_of_unity() {
+        let field = PrimeField::new(P998244353);
+        let w = root_of_unity(&field, 1 << 23).unwrap();
+        assert_eq!(Monoid::<Multiplication, u64>::pow(&field, w, &(1u64 << 23)), 1);
+        assert_eq!(Monoid::<Multiplication, u64>::pow(&field, w, &(1u64 << 22)), P998244353 - 1);
+        assert_eq!(root_of_unity(&field, 1 << 24), None);
+        assert_eq!(root_of_unity(&field, 1), Some(1));
+
+        let field = PrimeField::new(GOLDILOCKS);
+        let w = root_of_unity(&field, 1 << 32).unwrap();
+        assert_eq!(Monoid::<Multiplication, u64>::pow(&field, w, &(1u64 << 31)), GOLDILOCKS - 1);
+
+        // 96 = 2^5 * 3
+        let field = PrimeField::new(97u32);
+        assert!(root_of_unity(&field, 32).is_some());
+        assert_eq!(Ntt::new(&field, 64).err(), Some(Error::NoRootOfUnity));
+        assert_eq!(convolve(&field, &[1; 40], &[1; 40]), Err(Error::NoRootOfUnity));
+    }
+
+    #[test]
+    fn run_forward_and_inverse() {
+        let field = PrimeField::new(P998244353);
+        let ring = PolynomialRing::new(&field);
+        for size in [1, 2, 4, 8, 256] {
+            let ntt = Ntt::new(&field, size).unwrap();
+            let a = pseudo_random(size, 7, P998244353);
+
+            // the transform evaluates the polynomial at the powers of the root
+            let mut values = a.clone();
+            ntt.forward(&mut values);
+            let p = ring.polynomial(a.clone());
+            for (i, value) in values.iter().enumerate() {
+                assert_eq!(*value, ring.evaluate(&p, &Monoid::<Multiplication, u64>::pow(&field, *ntt.root(), &(i as u64))));
+            }
+
+            ntt.inverse(&mut values);
+            assert_eq!(values, a);
+        }
+    }
+
+    #[test]
+    fn run_convolution() {
+        for p in [P998244353, GOLDILOCKS] {
+            let field = PrimeField::new(p);
+            for (m, n) in [(1, 1), (3, 5), (64, 64), (100, 37), (300, 513)] {
+                let a = pseudo_random(m, 1, p);
+                let b = pseudo_random(n, 2, p);
+                assert_eq!(convolve(&field, &a, &b).unwrap(), schoolbook_convolve(&field, &a, &b));
+            }
+        }
+
+        let field = PrimeField::new(P998244353);
+        assert_eq!(convolve(&field, &[], &[1, 2]), Ok(vec![]));
+        // (1 + x)(1 - x) = 1 - x^2
+        assert_eq!(convolve(&field, &[1, 1], &[1, P998244353 - 1]), Ok(vec![1, 0, P998244353 - 1]));
+    }
+
+    #[test]
+    fn run_polynomial_multiplication_switches_to_ntt() {
+        // the schoolbook method is used below the threshold and when there are no roots of unity
+        // so every field gives the same products either way
+        for p in [P998244353, GOLDILOCKS, 1_000_000_007] {
+            let field = PrimeField::new(p);
+            let ring = PolynomialRing::new(&field);
+            let a = ring.polynomial(pseudo_random(200, 3, p));
+            let b = ring.polynomial(pseudo_random(150, 4, p));
+            let product = ring.multiply(a.clone(), b.clone());
+            assert_eq!(product.degree(), Some(348));
+            assert_eq!(product.coefficients(), schoolbook_convolve(&field, a.coefficients(), b.coefficients()));
+        }
+    }
+
+    #[ignore]
+    #[test]
+    fn run_benchmark_against_schoolbook() {
+        let field = PrimeField::new(P998244353);
+        for n in [16, 32, 64, 128, 256, 1024, 4096] {
+            let a = pseudo_random(n, 5, P998244353);
+            let b = pseudo_random(n, 6, P998244353);
+
+            let start = Instant::now();
+            let expected = schoolbook_convolve(&field, &a, &b);
+            let schoolbook = start.elapsed();
+
+            let start = Instant::now();
+            let result = convolve(&field, &a, &b).unwrap();
+            let ntt = start.elapsed();
+
+            assert_eq!(result, expected);
+            println!("n = {:5} schoolbook {:>12?} ntt {:>12?}", n, schoolbook, ntt);
+        }
+    }
+
+}
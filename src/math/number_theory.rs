@@ -0,0 +1,348 @@
+use crate::math::factor::factor;
+use crate::math::integer::UnsignedInt;
+
+pub use crate::math::euclid::gcd;
+
+/// Returns the least common multiple of a and b, which is zero if either is zero
+pub fn lcm<E: UnsignedInt>(a: E, b: E) -> E {
+    if a.is_zero() || b.is_zero() {
+        return E::zero();
+    }
+    let g = gcd(a.clone(), b.clone());
+    a / g * b
+}
+
+/// Returns the greatest common divisor using Stein's binary algorithm which replaces division
+/// with shifts and subtraction
+pub fn binary_gcd<E: UnsignedInt>(mut a: E, mut b: E) -> E {
+    if a.is_zero() {
+        return b;
+    }
+    if b.is_zero() {
+        return a;
+    }
+
+    // gcd(2^i a, 2^j b) = 2^min(i, j) gcd(a, b)
+    let i = a.trailing_zeros();
+    let j = b.trailing_zeros();
+    let shift = i.min(j);
+    a = a >> i;
+    b = b >> j;
+
+    // both a and b are odd here so their difference is even
+    loop {
+        if a > b {
+            std::mem::swap(&mut a, &mut b);
+        }
+        b = b - a.clone();
+        if b.is_zero() {
+            return a << shift;
+        }
+        let tz = b.trailing_zeros();
+        b = b >> tz;
+    }
+}
+
+/// Returns the integer square root, the largest r with r^2 <= n
+pub fn isqrt<E: UnsignedInt>(n: &E) -> E {
+    if n.is_zero() {
+        return E::zero();
+    }
+
+    // Newton's method decreases monotonically from any starting point above the root
+    let mut x = E::one() << n.bits().div_ceil(2);
+    loop {
+        let y = (x.clone() + n.clone() / x.clone()) >> 1;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// Returns the Jacobi symbol (a/n) which is 0, 1 or -1. For a prime n it is the Legendre symbol
+/// telling whether a is a square mod n.
+///
+/// Panics if n is even.
+pub fn jacobi<E: UnsignedInt>(a: &E, n: &E) -> i32 {
+    assert!(!n.is_even(), "the Jacobi symbol is only defined for odd n");
+
+    let (three, five, eight) = (E::from_u32(3), E::from_u32(5), E::from_u32(8));
+    let mut a = a.clone() % n.clone();
+    let mut n = n.clone();
+    let mut result = 1;
+    while !a.is_zero() {
+        // (2/n) is -1 when n = 3 or 5 mod 8
+        let twos = a.trailing_zeros();
+        a = a >> twos;
+        let n_mod_8 = n.clone() % eight.clone();
+        if twos % 2 == 1 && (n_mod_8 == three || n_mod_8 == five) {
+            result = -result;
+        }
+
+        // quadratic reciprocity flips the sign when both are 3 mod 4
+        if a.bit(1) && n.bit(1) {
+            result = -result;
+        }
+        std::mem::swap(&mut a, &mut n);
+        a = a % n.clone();
+    }
+
+    if n.is_one() {
+        result
+    } else {
+        0
+    }
+}
+
+/// Returns the Legendre symbol (a/p) for a prime p which is 0 when p divides a, 1 when a is a
+/// non-zero square mod p and -1 otherwise
+pub fn legendre<E: UnsignedInt>(a: &E, p: &E) -> i32 {
+    if p.is_even() {
+        // every element mod 2 is a square
+        return if a.is_even() { 0 } else { 1 };
+    }
+    jacobi(a, p)
+}
+
+/// Returns p^k
+fn prime_power<E: UnsignedInt>(p: &E, k: u32) -> E {
+    (0..k).fold(E::one(), |acc, _| acc * p.clone())
+}
+
+/// Euler's totient function φ(n) counts the integers in 1..=n which are coprime to n.
+///
+/// For n = p1^k1 ... pr^kr it is the product of p^(k-1) (p - 1) over the prime powers.
+pub fn totient<E: UnsignedInt>(n: &E) -> E {
+    if n.is_zero() {
+        return E::zero();
+    }
+    factor(n).iter().fold(E::one(), |acc, (p, k)| acc * prime_power(p, k - 1) * (p.clone() - E::one()))
+}
+
+/// Carmichael's function λ(n) is the smallest m such that a^m = 1 mod n for every a coprime
+/// to n. It divides φ(n) and is the exponent of the unit group mod n.
+pub fn carmichael<E: UnsignedInt>(n: &E) -> E {
+    if n.is_zero() {
+        return E::zero();
+    }
+    let two = E::from_u32(2);
+    factor(n).iter().fold(E::one(), |acc, (p, k)| {
+        let lambda = if *p == two && k >= 3 {
+            // the units mod 2^k are not cyclic for k >= 3
+            prime_power(p, k - 2)
+        } else {
+            prime_power(p, k - 1) * (p.clone() - E::one())
+        };
+        lcm(acc, lambda)
+    })
+}
+
+/// Returns the multiplicative order of a mod n, the smallest k > 0 with a^k = 1 mod n,
+/// or None if a is not coprime to n
+pub fn multiplicative_order<E: UnsignedInt>(a: &E, n: &E) -> Option<E> {
+    if n.is_zero() || !gcd(a.clone(), n.clone()).is_one() {
+        return None;
+    }
+    if n.is_one() {
+        return Some(E::one());
+    }
+
+    // the order divides λ(n) so remove prime factors of λ(n) while a^(order / q) is still one
+    let mut order = carmichael(n);
+    for (q, _) in factor(&order) {
+        loop {
+            let (reduced, r) = order.div_rem(&q);
+            if !r.is_zero() || !a.pow_mod(&reduced, n).is_one() {
+                break;
+            }
+            order = reduced;
+        }
+    }
+    Some(order)
+}
+
+/// Returns the smallest primitive root mod n, a generator of the units mod n, or None when the
+/// units are not cyclic. Primitive roots only exist for n = 1, 2, 4, p^k and 2p^k with p an odd prime.
+pub fn primitive_root<E: UnsignedInt>(n: &E) -> Option<E> {
+    if n.is_zero() {
+        return None;
+    }
+    if n.is_one() {
+        return Some(E::zero());
+    }
+
+    let phi = totient(n);
+    if carmichael(n) != phi {
+        return None;
+    }
+
+    // g generates the units when g^(φ/q) != 1 for every prime q dividing φ
+    let factors = factor(&phi);
+    let mut g = E::one();
+    while g < *n {
+        if gcd(g.clone(), n.clone()).is_one()
+            && factors.iter().all(|(q, _)| !g.pow_mod(&(phi.clone() / q.clone()), n).is_one())
+        {
+            return Some(g);
+        }
+        g = g + E::one();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::math::field::PrimeField;
+    use crate::math::group::{Monoid, Multiplication};
+    use crate::math::ring::NaturalNumbersModN;
+    use crate::math::BigUint;
+
+    #[test]
+    fn run_gcd_and_lcm() {
+        assert_eq!(gcd(48u32, 18), 6);
+        assert_eq!(lcm(4u32, 6), 12);
+        assert_eq!(lcm(0u32, 6), 0);
+        assert_eq!(lcm(u64::MAX, u64::MAX), u64::MAX);
+
+        for a in 0..100u64 {
+            for b in 0..100u64 {
+                assert_eq!(binary_gcd(a, b), gcd(a, b), "a = {}, b = {}", a, b);
+            }
+        }
+        assert_eq!(binary_gcd(3u128 << 100, 9u128 << 90), 3u128 << 90);
+
+        let a = BigUint::from_hex("123456789abcdef0123456789abcdef0").unwrap() * BigUint::from(12u32);
+        let b = BigUint::from_hex("fedcba9876543210fedcba9876543210").unwrap() * BigUint::from(18u32);
+        assert_eq!(binary_gcd(a.clone(), b.clone()), gcd(a.clone(), b.clone()));
+        assert_eq!(lcm(a.clone(), b.clone()) * gcd(a.clone(), b.clone()), a * b);
+    }
+
+    #[test]
+    fn run_isqrt() {
+        for n in 0..10_000u32 {
+            let r = isqrt(&n);
+            assert!(r * r <= n && (r + 1) * (r + 1) > n, "n = {}", n);
+        }
+        assert_eq!(isqrt(&u64::MAX), u32::MAX as u64);
+        assert_eq!(isqrt(&u128::MAX), u64::MAX as u128);
+        assert_eq!(isqrt(&BigUint::from(3u32).pow(101)), BigUint::from_hex("1074eca0979e156258ea2").unwrap());
+    }
+
+    #[test]
+    fn run_jacobi() {
+        assert_eq!(jacobi(&1001u32, &9907), -1);
+        assert_eq!(jacobi(&19u32, &45), 1);
+        assert_eq!(jacobi(&8u32, &21), -1);
+        assert_eq!(jacobi(&5u32, &21), 1);
+        assert_eq!(jacobi(&6u32, &15), 0);
+        assert_eq!(jacobi(&0u32, &1), 1);
+
+        // for a prime it matches Euler's criterion a^((p-1)/2) mod p
+        for p in [3u64, 5, 7, 11, 101, 65521] {
+            for a in 0..100u64 {
+                let euler = match a.pow_mod(&((p - 1) / 2), &p) {
+                    0 => 0,
+                    1 => 1,
+                    _ => -1,
+                };
+                assert_eq!(jacobi(&a, &p), euler, "a = {}, p = {}", a, p);
+            }
+        }
+
+        // and it is multiplicative in n
+        for a in 0..50u32 {
+            assert_eq!(jacobi(&a, &(9 * 35)), jacobi(&a, &9) * jacobi(&a, &35));
+        }
+    }
+
+    #[test]
+    fn run_legendre() {
+        // the squares mod 11 are 1, 3, 4, 5 and 9
+        let symbols: Vec<i32> = (0..11u32).map(|a| legendre(&a, &11)).collect();
+        assert_eq!(symbols, vec![0, 1, -1, 1, 1, 1, -1, -1, -1, 1, -1]);
+        assert_eq!(legendre(&3u32, &2), 1);
+        assert_eq!(legendre(&4u32, &2), 0);
+    }
+
+    #[test]
+    fn run_totient_and_carmichael() {
+        // values from OEIS A000010 and A002322
+        let phi = [1u32, 1, 2, 2, 4, 2, 6, 4, 6, 4, 10, 4, 12, 6, 8, 8, 16, 6, 18, 8];
+        let lambda = [1u32, 1, 2, 2, 4, 2, 6, 2, 6, 4, 10, 2, 12, 6, 4, 4, 16, 6, 18, 4];
+        for n in 1..=20u32 {
+            assert_eq!(totient(&n), phi[n as usize - 1], "φ({})", n);
+            assert_eq!(carmichael(&n), lambda[n as usize - 1], "λ({})", n);
+        }
+
+        // the same functions work for BigUint
+        let n = BigUint::from(2u32).pow(70) * BigUint::from(3u32).pow(5);
+        assert_eq!(totient(&n), BigUint::from(2u32).pow(69) * BigUint::from(2u32) * BigUint::from(3u32).pow(4));
+        assert_eq!(carmichael(&n), lcm(BigUint::from(2u32).pow(68), BigUint::from(162u32)));
+    }
+
+    #[test]
+    fn run_multiplicative_order_and_primitive_root() {
+        assert_eq!(multiplicative_order(&2u32, &7), Some(3));
+        assert_eq!(multiplicative_order(&3u32, &7), Some(6));
+        assert_eq!(multiplicative_order(&2u32, &8), None);
+
+        assert_eq!(primitive_root(&7u32), Some(3));
+        assert_eq!(primitive_root(&1_000_000_007u64), Some(5));
+        assert_eq!(primitive_root(&998_244_353u64), Some(3));
+        assert_eq!(primitive_root(&18u32), Some(5));
+        assert_eq!(primitive_root(&8u32), None);
+        assert_eq!(primitive_root(&15u32), None);
+
+        // the order of every element divides λ(n) and a primitive root reaches φ(n)
+        for n in 2..200u32 {
+            for a in 1..n {
+                if let Some(order) = multiplicative_order(&a, &n) {
+                    assert_eq!(carmichael(&n) % order, 0);
+                    assert!(a.pow_mod(&order, &n).is_one());
+                }
+            }
+            if let Some(g) = primitive_root(&n) {
+                assert_eq!(multiplicative_order(&g, &n), Some(totient(&n)));
+            }
+        }
+    }
+
+    #[test]
+    fn run_eulers_theorem() {
+        // a^φ(n) = 1 mod n for every a coprime to n
+        for n in 2..300u32 {
+            let ring = NaturalNumbersModN::new(n);
+            let phi = totient(&n);
+            for a in (1..n).filter(|&a| gcd(a, n) == 1) {
+                assert_eq!(Monoid::<Multiplication, u32>::pow(&ring, a, &phi), 1, "a = {}, n = {}", a, n);
+            }
+        }
+
+        // and for a large composite modulus
+        let n = 4_294_967_291u128 * 65_521;
+        let ring = NaturalNumbersModN::new(n);
+        let phi = totient(&n);
+        assert_eq!(phi, 4_294_967_290 * 65_520);
+        assert_eq!(Monoid::<Multiplication, u128>::pow(&ring, 0x1234_5678_9abc, &phi), 1);
+    }
+
+    #[test]
+    fn run_fermats_little_theorem() {
+        // a^(p-1) = 1 mod p and a^p = a mod p for a prime p
+        for p in [2u64, 3, 5, 7, 11, 13, 65521, 1_000_000_007, u64::MAX - 58] {
+            let field = PrimeField::new(p);
+            for a in [1u64, 2, 3, 12345, p - 1] {
+                let a = a % p;
+                if a == 0 {
+                    continue;
+                }
+                assert_eq!(Monoid::<Multiplication, u64>::pow(&field, a, &(p - 1)), 1);
+                assert_eq!(Monoid::<Multiplication, u64>::pow(&field, a, &p), a);
+            }
+        }
+    }
+
+}
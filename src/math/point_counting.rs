@@ -0,0 +1,420 @@
+use crate::math::elliptic_curve::{EllipticCurve, Point};
+use crate::math::factor::factor;
+use crate::math::field::PrimeField;
+use crate::math::group::{Addition, Group, Monoid};
+use crate::math::integer::UnsignedInt;
+use crate::math::number_theory::{isqrt, lcm, legendre};
+use crate::math::prime_gen::random_prime;
+use crate::math::primes::{is_prime, random_below};
+use crate::math::ring::Ring;
+use crate::math::roots::Roots;
+use crate::math::Error;
+use ring::rand::SecureRandom;
+use std::collections::HashMap;
+
+/// The number of random points tried by baby step giant step over fields of at most
+/// MESTRE_BOUND elements before counting naively
+const MAX_POINTS: usize = 32;
+
+/// Mestre showed that over a field of more than this many elements either a curve or its
+/// quadratic twist has a point whose order has a single multiple in the Hasse interval
+const MESTRE_BOUND: u32 = 229;
+
+/// Returns the number of points including infinity by counting the solutions for every x.
+///
+/// x^3 + ax + b has two square roots when it is a non-zero square, one when it is zero and none
+/// otherwise, which is 1 + its Legendre symbol. This takes O(p) steps so suits small fields.
+///
+/// Panics if p uses the top bit of E, as the number of points may not fit.
+pub fn count_points_naive<E: UnsignedInt>(curve: &EllipticCurve<PrimeField<E>, E>) -> E {
+    let p = curve.field().modulus();
+    check_room(p);
+    let mut count = E::one();
+    let mut x = E::zero();
+    while x < *p {
+        count = match legendre(&curve.rhs(&x), p) {
+            1 => count + E::from_u32(2),
+            0 => count + E::one(),
+            _ => count,
+        };
+        x = x + E::one();
+    }
+    count
+}
+
+/// Returns the number of points including infinity using baby step giant step in the Hasse
+/// interval p + 1 - 2√p <= #E <= p + 1 + 2√p, taking O(p^(1/4)) steps per point.
+///
+/// Each random point P has an order dividing #E, found from a multiple of it in the interval.
+/// Once the lcm of these orders has a single multiple in the interval that multiple is #E. A
+/// curve with a large non-cyclic part has a group exponent too small for that, so points are
+/// also taken from the quadratic twist E', whose count is 2p + 2 - #E. By Mestre's theorem one
+/// of the two exponents is large enough when p > 229, while over smaller fields the count falls
+/// back to count_points_naive after MAX_POINTS points.
+///
+/// Panics if p uses the top bit of E, as the number of points may not fit.
+pub fn count_points_bsgs<E: UnsignedInt>(curve: &EllipticCurve<PrimeField<E>, E>, rng: &dyn SecureRandom) -> E {
+    let p = curve.field().modulus();
+    check_room(p);
+    let (low, high) = hasse_interval(p);
+    let twist = quadratic_twist(curve);
+
+    let mut exponents = [E::one(), E::one()];
+    let mut points = 0;
+    loop {
+        if points == MAX_POINTS && *p <= E::from_u32(MESTRE_BOUND) {
+            return count_points_naive(curve);
+        }
+        points += 1;
+
+        for (i, on) in [curve, &twist].into_iter().enumerate() {
+            let point = random_point(on, rng);
+            let Some(multiple) = multiple_in_interval(on, &point, &low, &high) else {
+                continue;
+            };
+            exponents[i] = lcm(exponents[i].clone(), order_of_point(on, &point, &multiple));
+            match (i, unique_multiple(&exponents[i], &low, &high)) {
+                (0, Some(order)) => return order,
+                // the interval is centred on p + 1 so low + high = 2p + 2
+                (_, Some(order)) => return low.clone() + (high.clone() - order),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Returns true if the curve has exactly p points, when its trace is one. The discrete log on
+/// an anomalous curve maps to the additive group of the field, so it is easy.
+pub fn is_anomalous<E: UnsignedInt>(curve: &EllipticCurve<PrimeField<E>, E>, order: &E) -> bool {
+    order == curve.field().modulus()
+}
+
+/// Returns true if p divides the trace p + 1 - #E. For p > 3 the Hasse bound makes this the
+/// same as #E = p + 1, and such curves have embedding degree two.
+pub fn is_supersingular<E: UnsignedInt>(curve: &EllipticCurve<PrimeField<E>, E>, order: &E) -> bool {
+    let p = curve.field().modulus();
+    (p.clone() + E::one()).sub_mod(&(order.clone() % p.clone()), p).is_zero()
+}
+
+/// Returns the embedding degree of a subgroup of prime order n, the smallest k with n | p^k - 1,
+/// or None if it is above max. The MOV attack moves discrete logs into the multiplicative group
+/// of a field of p^k elements, so curves with a small embedding degree are weak.
+///
+/// The degree is the order of p mod n which divides n - 1, but factoring n - 1 is impractical for
+/// cryptographic n so the powers are tried in turn up to max.
+pub fn embedding_degree<E: UnsignedInt>(p: &E, n: &E, max: u32) -> Option<u32> {
+    let base = p.clone() % n.clone();
+    let mut power = base.clone();
+    for k in 1..=max {
+        if power.is_one() {
+            return Some(k);
+        }
+        power = power.mul_mod(&base, n);
+    }
+    None
+}
+
+/// A curve of prime order with a generator, as found by random_prime_order_curve
+pub struct ToyCurve<E> {
+    pub curve: EllipticCurve<PrimeField<E>, E>,
+    pub order: E,
+    pub generator: Point<E>,
+}
+
+/// Returns a random curve over a random prime field of the given bit length whose number of
+/// points is prime, so every point other than infinity generates the group.
+///
+/// Returns InvalidBitLength if bits is below 3, as the field must have characteristic above 3,
+/// or not narrower than E, which leaves no room for a number of points above p.
+pub fn random_prime_order_curve<E: UnsignedInt>(rng: &dyn SecureRandom, bits: usize) -> Result<ToyCurve<E>, Error> {
+    // 2^bits is built by doubling so it wraps to zero rather than overflowing when E has no more
+    // than bits bits
+    let top = (0..bits).fold(E::one(), |acc, _| acc << 1);
+    if bits < 3 || top.is_zero() {
+        return Err(Error::InvalidBitLength);
+    }
+    let p: E = random_prime(rng, bits)?;
+
+    loop {
+        let (a, b) = (random_below(rng, &p), random_below(rng, &p));
+        let Ok(curve) = EllipticCurve::try_new(PrimeField::new(p.clone()), a, b) else {
+            continue;
+        };
+        let order = count_points_bsgs(&curve, rng);
+        if is_prime(&order) {
+            let generator = random_point(&curve, rng);
+            return Ok(ToyCurve { curve, order, generator });
+        }
+    }
+}
+
+/// Returns the integers nearest the Hasse bounds p + 1 ± 2√p which contain #E
+fn hasse_interval<E: UnsignedInt>(p: &E) -> (E, E) {
+    // the width is isqrt(4p) which is 2r or 2r + 1 for r = isqrt(p), the latter when
+    // (2r + 1)^2 <= 4p or equivalently p - r^2 > r, found without computing 4p
+    let r = isqrt(p);
+    let twice_r = r.clone() + r.clone();
+    let width = if p.clone() - r.clone() * r.clone() > r { twice_r + E::one() } else { twice_r };
+    let middle = p.clone() + E::one();
+    (middle.clone() - width.clone(), middle + width)
+}
+
+/// Returns the only multiple of the exponent in [low, high], or None if there are several. The
+/// first multiple at or above low is at most high when the exponent divides a count in the
+/// interval.
+fn unique_multiple<E: UnsignedInt>(exponent: &E, low: &E, high: &E) -> Option<E> {
+    let (quotient, remainder) = low.div_rem(exponent);
+    let first = if remainder.is_zero() { low.clone() } else { (quotient + E::one()) * exponent.clone() };
+    if high.clone() - first.clone() < *exponent {
+        Some(first)
+    } else {
+        None
+    }
+}
+
+/// Returns the curve y^2 = x^3 + ad^2 x + bd^3 for a non-residue d. Each x gives two points on
+/// exactly one of the curve and its twist unless x^3 + ax + b is zero, so together they have
+/// 2p + 2 points.
+fn quadratic_twist<E: UnsignedInt>(curve: &EllipticCurve<PrimeField<E>, E>) -> EllipticCurve<PrimeField<E>, E> {
+    let field = curve.field();
+    let mut d = E::from_u32(2);
+    while legendre(&d, field.modulus()) != -1 {
+        d = d + E::one();
+    }
+    let d_squared = field.multiply(d.clone(), d.clone());
+    let a = field.multiply(curve.a().clone(), d_squared.clone());
+    let b = field.multiply(curve.b().clone(), field.multiply(d_squared, d));
+    EllipticCurve::new(field.clone(), a, b)
+}
+
+/// Panics if p uses the top bit of E. Otherwise p < 2^(n-1) for E of n bits so every value up
+/// to p + 1 + 2√p, and the steps just beyond it, fit.
+fn check_room<E: UnsignedInt>(p: &E) {
+    assert!((p.clone() << 1) >> 1 == *p, "the number of points may not fit in the element type");
+}
+
+/// Returns a random point other than infinity
+fn random_point<E: UnsignedInt>(curve: &EllipticCurve<PrimeField<E>, E>, rng: &dyn SecureRandom) -> Point<E> {
+    let field = curve.field();
+    loop {
+        let x = random_below(rng, field.modulus());
+        if let Some(y) = field.sqrt(curve.rhs(&x)) {
+            // either root is equally likely
+            let y = if random_below(rng, &E::from_u32(2)).is_zero() { y } else { <PrimeField<E> as Group<Addition, E>>::inverse(field, y) };
+            return Point::Affine { x, y };
+        }
+    }
+}
+
+/// Returns an m in [low, high] with m.P = O using baby step giant step, or None if there is none.
+///
+/// The baby steps j.P for j < s are stored so the giant steps (low + is).P can be matched
+/// against -j.P, which gives (low + is + j).P = O.
+fn multiple_in_interval<E: UnsignedInt>(curve: &EllipticCurve<PrimeField<E>, E>, point: &Point<E>, low: &E, high: &E) -> Option<E> {
+    let s = isqrt(&(high.clone() - low.clone())) + E::one();
+    let mut baby_steps = HashMap::new();
+    let mut step = Point::Infinity;
+    let mut j = E::zero();
+    while j < s {
+        baby_steps.entry(step.clone()).or_insert(j.clone());
+        step = curve.apply(step, point.clone());
+        j = j + E::one();
+    }
+
+    // step is now s.P
+    let mut giant = curve.multiply(point, low);
+    let mut base = low.clone();
+    while base <= *high {
+        if let Some(j) = baby_steps.get(&curve.inverse(giant.clone())) {
+            let m = base.clone() + j.clone();
+            if m <= *high {
+                return Some(m);
+            }
+        }
+        giant = curve.apply(giant, step.clone());
+        base = base + s.clone();
+    }
+    None
+}
+
+/// Returns the order of a point given a multiple of it, by dividing out primes while the
+/// quotient still takes the point to infinity
+fn order_of_point<E: UnsignedInt>(curve: &EllipticCurve<PrimeField<E>, E>, point: &Point<E>, multiple: &E) -> E {
+    let mut order = multiple.clone();
+    for (q, _) in factor(multiple).iter() {
+        loop {
+            let (reduced, r) = order.div_rem(q);
+            if !r.is_zero() || !curve.multiply(point, &reduced).is_infinity() {
+                break;
+            }
+            order = reduced;
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::math::named_curves::P256;
+    use ring::rand::SystemRandom;
+
+    #[test]
+    fn run_count_points() {
+        let rng = SystemRandom::new();
+
+        // y^2 = x^3 + 2x + 3 mod 1009
+        let curve = EllipticCurve::new(PrimeField::new(1009u64), 2, 3);
+        assert_eq!(count_points_naive(&curve), 1068);
+        assert_eq!(count_points_bsgs(&curve, &rng), 1068);
+
+        // naive and baby step giant step agree on random curves over fields of several sizes
+        for p in [5u64, 7, 11, 101, 65537, 100_003] {
+            for _ in 0..5 {
+                let (a, b) = (random_below(&rng, &p), random_below(&rng, &p));
+                let Ok(curve) = EllipticCurve::try_new(PrimeField::new(p), a, b) else { continue };
+                let count = count_points_naive(&curve);
+                assert_eq!(count_points_bsgs(&curve, &rng), count, "p = {} a = {} b = {}", p, a, b);
+
+                let (low, high) = hasse_interval(&p);
+                assert!(low <= count && count <= high);
+            }
+        }
+    }
+
+    #[test]
+    fn run_count_points_with_a_small_exponent() {
+        let rng = SystemRandom::new();
+
+        // y^2 = x^3 + 2 mod 241 is Z/15 x Z/15 so every multiple of 15 in [211, 273] is a candidate
+        let curve = EllipticCurve::new(PrimeField::new(241u32), 0, 2);
+        assert_eq!(count_points_bsgs(&curve, &rng), 225);
+
+        // y^2 = x^3 + 1 mod n^2 - n + 1 is Z/n x Z/n, where only the twist gives the count
+        for n in [174u64, 1_048_578] {
+            let curve = EllipticCurve::new(PrimeField::new(n * n - n + 1), 0, 1);
+            assert_eq!(count_points_bsgs(&curve, &rng), n * n);
+            assert!(curve.multiply(&random_point(&curve, &rng), &n).is_infinity());
+        }
+        assert_eq!(count_points_naive(&EllipticCurve::new(PrimeField::new(30_103u32), 0, 1)), 30_276);
+
+        // the twist of a curve has the remaining points
+        let curve = EllipticCurve::new(PrimeField::new(1009u32), 2, 3);
+        assert_eq!(count_points_naive(&curve) + count_points_naive(&quadratic_twist(&curve)), 2 * 1009 + 2);
+    }
+
+    #[test]
+    fn run_count_points_on_a_larger_field() {
+        let rng = SystemRandom::new();
+        let p: u64 = random_prime(&rng, 40).unwrap();
+        let curve = EllipticCurve::new(PrimeField::new(p), 3, 7);
+        let count = count_points_bsgs(&curve, &rng);
+
+        // every point is taken to infinity by the count
+        for _ in 0..10 {
+            assert!(curve.multiply(&random_point(&curve, &rng), &count).is_infinity());
+        }
+    }
+
+    #[test]
+    fn run_anomalous_and_supersingular_curves() {
+        // y^2 = x^3 + x is supersingular when p = 3 mod 4, so has p + 1 points
+        let curve = EllipticCurve::new(PrimeField::new(1019u32), 1, 0);
+        let order = count_points_naive(&curve);
+        assert_eq!(order, 1020);
+        assert!(is_supersingular(&curve, &order));
+        assert!(!is_anomalous(&curve, &order));
+
+        // 1020 = 2^2 3 5 17 and 1019 = -1 mod 17 so the subgroup of order 17 has embedding degree 2
+        assert_eq!(embedding_degree(&1019u32, &17, 10), Some(2));
+
+        // y^2 = x^3 + 1 is supersingular when p = 2 mod 3
+        let curve = EllipticCurve::new(PrimeField::new(1013u32), 0, 1);
+        assert!(is_supersingular(&curve, &count_points_naive(&curve)));
+
+        // search for an anomalous curve over a small field
+        let p = 47u32;
+        let (curve, order) = (0..p)
+            .flat_map(|a| (0..p).map(move |b| (a, b)))
+            .filter_map(|(a, b)| EllipticCurve::try_new(PrimeField::new(p), a, b).ok())
+            .map(|curve| {
+                let order = count_points_naive(&curve);
+                (curve, order)
+            })
+            .find(|(curve, order)| is_anomalous(curve, order))
+            .unwrap();
+        assert_eq!(order, p);
+        assert!(!is_supersingular(&curve, &order));
+        assert_eq!(embedding_degree(&p, &order, 100), None);
+
+        let curve = EllipticCurve::new(PrimeField::new(1009u64), 2, 3);
+        assert!(!is_anomalous(&curve, &1068) && !is_supersingular(&curve, &1068));
+    }
+
+    #[test]
+    fn run_embedding_degree() {
+        assert_eq!(embedding_degree(&7u32, &3, 10), Some(1));
+        assert_eq!(embedding_degree(&2u32, &7, 10), Some(3));
+
+        // P-256 is safe from the MOV attack
+        assert_eq!(embedding_degree(&P256.curve().field().modulus().clone(), &P256.order(), 100), None);
+    }
+
+    #[test]
+    fn run_random_prime_order_curve() {
+        let rng = SystemRandom::new();
+        for bits in [3, 8, 20, 32] {
+            let toy: ToyCurve<u64> = random_prime_order_curve(&rng, bits).unwrap();
+            let p = *toy.curve.field().modulus();
+            assert_eq!(p.bits(), bits);
+            assert!(is_prime(&toy.order));
+            assert!(toy.curve.is_on_curve(&toy.generator));
+            assert!(!toy.generator.is_infinity());
+            assert!(toy.curve.multiply(&toy.generator, &toy.order).is_infinity());
+            if bits <= 20 {
+                assert_eq!(count_points_naive(&toy.curve), toy.order);
+            }
+        }
+        assert_eq!(random_prime_order_curve::<u64>(&rng, 2).err(), Some(Error::InvalidBitLength));
+        assert_eq!(random_prime_order_curve::<u32>(&rng, 33).err(), Some(Error::InvalidBitLength));
+    }
+
+    #[test]
+    fn run_random_prime_order_curve_at_the_top_of_u32() {
+        let rng = SystemRandom::new();
+
+        // 31 bits leaves room for a number of points above 2^31
+        let toy: ToyCurve<u32> = random_prime_order_curve(&rng, 31).unwrap();
+        assert_eq!(toy.curve.field().modulus().bits(), 31);
+        assert!(is_prime(&toy.order));
+        assert!(toy.curve.multiply(&toy.generator, &toy.order).is_infinity());
+
+        // the same curve counted with u64 arithmetic agrees
+        let (a, b) = (*toy.curve.a() as u64, *toy.curve.b() as u64);
+        let wide = EllipticCurve::new(PrimeField::new(*toy.curve.field().modulus() as u64), a, b);
+        assert_eq!(count_points_bsgs(&wide, &rng), toy.order as u64);
+
+        // a 32 bit prime may have more than 2^32 points
+        assert_eq!(random_prime_order_curve::<u32>(&rng, 32).err(), Some(Error::InvalidBitLength));
+    }
+
+    #[test]
+    fn run_hasse_interval() {
+        // isqrt(4p) without overflow, including where it is odd
+        for p in [5u32, 7, 11, 101, 65537, 2_147_483_647] {
+            let width = (4.0 * p as f64).sqrt() as u32;
+            assert_eq!(hasse_interval(&p), (p + 1 - width, p + 1 + width), "p = {}", p);
+        }
+        assert_eq!(hasse_interval(&4_294_967_291u64), (4_294_967_292 - 131_071, 4_294_967_292 + 131_071));
+    }
+
+    #[test]
+    #[should_panic(expected = "the number of points may not fit in the element type")]
+    fn run_count_points_without_room() {
+        let rng = SystemRandom::new();
+        let curve = EllipticCurve::new(PrimeField::new(4_294_967_291u32), 3, 7);
+        count_points_bsgs(&curve, &rng);
+    }
+
+}
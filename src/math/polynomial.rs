@@ -0,0 +1,370 @@
+use crate::math::field::Field;
+use crate::math::group::{AbelianGroup, Addition, Group, Monoid, Multiplication, ScalarMul};
+use crate::math::ring::Ring;
+use crate::math::Error;
+use std::fmt;
+
+/// A polynomial a_0 + a_1 x + ... + a_n x^n stored with the constant coefficient first.
+///
+/// The coefficient arithmetic comes from the ring the coefficients belong to, so polynomials are
+/// built and combined through a PolynomialRing which also keeps them normalised without
+/// trailing zero coefficients. The zero polynomial has no coefficients.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Polynomial<E> {
+    coefficients: Vec<E>,
+}
+
+impl<E> Polynomial<E> {
+    /// Returns the coefficients with the constant first
+    pub fn coefficients(&self) -> &[E] {
+        &self.coefficients
+    }
+
+    /// Returns the coefficient of x^i which is None above the degree
+    pub fn coefficient(&self, i: usize) -> Option<&E> {
+        self.coefficients.get(i)
+    }
+
+    /// Returns the degree or None for the zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    /// Returns the coefficient of the highest power of x
+    pub fn leading_coefficient(&self) -> Option<&E> {
+        self.coefficients.last()
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for Polynomial<E> {
+    /// Formats from the highest power down such as 3x^2 + x + 6. Coefficients are only known
+    /// through Display here so ones which display as 0 are skipped and 1 is left implicit.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut terms = Vec::new();
+        for (i, a) in self.coefficients.iter().enumerate().rev() {
+            let a = a.to_string();
+            if a == "0" {
+                continue;
+            }
+            let a = if a == "1" && i > 0 { String::new() } else { a };
+            terms.push(match i {
+                0 => a,
+                1 => format!("{}x", a),
+                _ => format!("{}x^{}", a, i),
+            });
+        }
+        if terms.is_empty() {
+            write!(f, "0")
+        } else {
+            write!(f, "{}", terms.join(" + "))
+        }
+    }
+}
+
+/// The polynomials with coefficients in a ring form a ring themselves, written R[x].
+///
+/// When the coefficients come from a field polynomials can also be divided with remainder, which
+/// gives a Euclidean algorithm for their greatest common divisor.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PolynomialRing<'a, R> {
+    ring: &'a R,
+}
+
+impl<'a, R> PolynomialRing<'a, R> {
+    pub fn new(ring: &'a R) -> Self {
+        PolynomialRing { ring }
+    }
+
+    /// Returns the ring the coefficients belong to
+    pub fn ring(&self) -> &'a R {
+        self.ring
+    }
+
+    /// Returns the polynomial with the given coefficients, constant first, dropping any trailing zeros
+    pub fn polynomial<E>(&self, coefficients: Vec<E>) -> Polynomial<E>
+    where
+        R: Ring<E>,
+        E: Clone + PartialEq,
+    {
+        let zero = <R as Monoid<Addition, E>>::identity(self.ring);
+        let mut coefficients = coefficients;
+        while coefficients.last() == Some(&zero) {
+            coefficients.pop();
+        }
+        Polynomial { coefficients }
+    }
+
+    /// Returns the constant polynomial c
+    pub fn constant<E>(&self, c: E) -> Polynomial<E>
+    where
+        R: Ring<E>,
+        E: Clone + PartialEq,
+    {
+        self.polynomial(vec![c])
+    }
+
+    /// Returns c x^n
+    pub fn monomial<E>(&self, c: E, n: usize) -> Polynomial<E>
+    where
+        R: Ring<E>,
+        E: Clone + PartialEq,
+    {
+        let mut coefficients = vec![<R as Monoid<Addition, E>>::identity(self.ring); n];
+        coefficients.push(c);
+        self.polynomial(coefficients)
+    }
+
+    /// Evaluates p at x using Horner's rule a_0 + x (a_1 + x (a_2 + ...)) which needs one
+    /// multiplication and one addition per coefficient
+    pub fn evaluate<E>(&self, p: &Polynomial<E>, x: &E) -> E
+    where
+        R: Ring<E>,
+        E: Clone,
+    {
+        p.coefficients.iter().rev().fold(<R as Monoid<Addition, E>>::identity(self.ring), |acc, a| {
+            self.ring.add(self.ring.multiply(acc, x.clone()), a.clone())
+        })
+    }
+
+    /// Returns the formal derivative where a x^i becomes i a x^(i-1)
+    pub fn derivative<E>(&self, p: &Polynomial<E>) -> Polynomial<E>
+    where
+        R: Ring<E>,
+        E: Clone + PartialEq,
+    {
+        let coefficients = p.coefficients.iter().enumerate().skip(1).map(|(i, a)| self.ring.scalar_mul(a.clone(), &(i as u64)));
+        self.polynomial(coefficients.collect())
+    }
+
+    /// Returns the composition p(q(x)) using Horner's rule over polynomials
+    pub fn compose<E>(&self, p: &Polynomial<E>, q: &Polynomial<E>) -> Polynomial<E>
+    where
+        R: Ring<E>,
+        E: Clone + PartialEq,
+    {
+        p.coefficients.iter().rev().fold(self.polynomial(vec![]), |acc, a| {
+            Ring::add(self, Ring::multiply(self, acc, q.clone()), self.constant(a.clone()))
+        })
+    }
+
+    /// Returns the quotient and remainder of dividing a by b so that a = bq + r with r of
+    /// lower degree than b, or an error if b is zero
+    pub fn div_rem<E>(&self, a: &Polynomial<E>, b: &Polynomial<E>) -> Result<(Polynomial<E>, Polynomial<E>), Error>
+    where
+        R: Field<E>,
+        E: Clone + PartialEq,
+    {
+        let (Some(b_degree), Some(lead)) = (b.degree(), b.leading_coefficient()) else {
+            return Err(Error::DivisionByZero);
+        };
+        let lead_inverse = <R as Group<Multiplication, E>>::checked_inverse(self.ring, lead.clone())?;
+
+        let zero = <R as Monoid<Addition, E>>::identity(self.ring);
+        let mut remainder = a.coefficients.clone();
+        let mut quotient = vec![zero; remainder.len().saturating_sub(b_degree)];
+
+        // long division cancels the leading term of the remainder one power at a time
+        while remainder.len() > b_degree {
+            let shift = remainder.len() - 1 - b_degree;
+            let factor = self.ring.multiply(remainder.last().unwrap().clone(), lead_inverse.clone());
+            for (i, b_i) in b.coefficients.iter().enumerate() {
+                let product = self.ring.multiply(factor.clone(), b_i.clone());
+                remainder[shift + i] = self.ring.subtract(remainder[shift + i].clone(), product);
+            }
+            quotient[shift] = factor;
+            remainder.pop();
+        }
+
+        Ok((self.polynomial(quotient), self.polynomial(remainder)))
+    }
+
+    /// Returns the monic greatest common divisor of a and b, which is zero only when both are zero
+    pub fn gcd<E>(&self, a: &Polynomial<E>, b: &Polynomial<E>) -> Polynomial<E>
+    where
+        R: Field<E>,
+        E: Clone + PartialEq,
+    {
+        let (mut a, mut b) = (a.clone(), b.clone());
+        while !b.is_zero() {
+            let (_, r) = self.div_rem(&a, &b).unwrap();
+            a = std::mem::replace(&mut b, r);
+        }
+        self.monic(&a)
+    }
+
+    /// Divides by the leading coefficient so it becomes one
+    pub fn monic<E>(&self, p: &Polynomial<E>) -> Polynomial<E>
+    where
+        R: Field<E>,
+        E: Clone + PartialEq,
+    {
+        match p.leading_coefficient() {
+            None => p.clone(),
+            Some(lead) => {
+                let inverse = <R as Group<Multiplication, E>>::inverse(self.ring, lead.clone());
+                self.polynomial(p.coefficients.iter().map(|a| self.ring.multiply(a.clone(), inverse.clone())).collect())
+            }
+        }
+    }
+}
+
+impl<R: Ring<E>, E: Clone + PartialEq> Monoid<Addition, Polynomial<E>> for PolynomialRing<'_, R> {
+    fn apply(&self, e1: Polynomial<E>, e2: Polynomial<E>) -> Polynomial<E> {
+        let (mut long, short) = if e1.coefficients.len() >= e2.coefficients.len() { (e1, e2) } else { (e2, e1) };
+        for (a, b) in long.coefficients.iter_mut().zip(short.coefficients) {
+            *a = self.ring.add(a.clone(), b);
+        }
+        self.polynomial(long.coefficients)
+    }
+
+    fn identity(&self) -> Polynomial<E> {
+        Polynomial { coefficients: vec![] }
+    }
+}
+
+impl<R: Ring<E>, E: Clone + PartialEq> Group<Addition, Polynomial<E>> for PolynomialRing<'_, R> {
+    fn inverse(&self, e: Polynomial<E>) -> Polynomial<E> {
+        let coefficients = e.coefficients.into_iter().map(|a| <R as Group<Addition, E>>::inverse(self.ring, a));
+        self.polynomial(coefficients.collect())
+    }
+}
+
+impl<R: Ring<E>, E: Clone + PartialEq> AbelianGroup<Addition, Polynomial<E>> for PolynomialRing<'_, R> {}
+
+impl<R: Ring<E>, E: Clone + PartialEq> Monoid<Multiplication, Polynomial<E>> for PolynomialRing<'_, R> {
+    /// Multiplies through the coefficient ring's convolution, which switches to a number theoretic
+    /// transform for long polynomials over a suitable PrimeField
+    fn apply(&self, e1: Polynomial<E>, e2: Polynomial<E>) -> Polynomial<E> {
+        self.polynomial(self.ring.convolve(&e1.coefficients, &e2.coefficients))
+    }
+
+    fn identity(&self) -> Polynomial<E> {
+        self.constant(<R as Monoid<Multiplication, E>>::identity(self.ring))
+    }
+}
+
+impl<R: Ring<E>, E: Clone + PartialEq> Ring<Polynomial<E>> for PolynomialRing<'_, R> {}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::math::elem::Elem;
+    use crate::math::field::{Fp, PrimeField};
+    use crate::math::laws::{check_ring, Elements};
+    use crate::math::ring::NaturalNumbersModN;
+    use ring::rand::{SecureRandom, SystemRandom};
+
+    #[test]
+    fn run_polynomial_arithmetic() {
+        let field = PrimeField::new(7u32);
+        let ring = PolynomialRing::new(&field);
+        let p = |c: Vec<u32>| ring.polynomial(c);
+
+        // (x + 1)(x + 6) = x^2 + 6 since 7 = 0
+        let a = p(vec![1, 1]);
+        let b = p(vec![6, 1]);
+        assert_eq!(ring.multiply(a.clone(), b.clone()), p(vec![6, 0, 1]));
+        assert_eq!(ring.add(a.clone(), b.clone()), p(vec![0, 2]));
+        assert_eq!(ring.subtract(a.clone(), a.clone()), p(vec![]));
+        assert_eq!(ring.add(p(vec![1, 2, 3]), p(vec![0, 0, 4])), p(vec![1, 2]));
+
+        assert_eq!(p(vec![1, 2, 0, 0]).degree(), Some(1));
+        assert_eq!(p(vec![0]).degree(), None);
+        assert_eq!(p(vec![6, 0, 1]).to_string(), "x^2 + 6");
+        assert_eq!(p(vec![6, 1, 3]).to_string(), "3x^2 + x + 6");
+        assert_eq!(p(vec![]).to_string(), "0");
+
+        // the ring operators work through Elem
+        let x = |c| Elem::new(&ring, p(c));
+        assert_eq!((x(vec![1, 1]) * x(vec![1, 1]) - x(vec![1])).into_value(), p(vec![0, 2, 1]));
+        assert_eq!(x(vec![1, 1]).pow(&7u32).into_value(), p(vec![1, 0, 0, 0, 0, 0, 0, 1]));
+    }
+
+    #[test]
+    fn run_evaluate_derivative_and_compose() {
+        let field = PrimeField::new(101u32);
+        let ring = PolynomialRing::new(&field);
+        let p = ring.polynomial(vec![5, 0, 3, 2]);
+
+        // 2x^3 + 3x^2 + 5 at 4 is 128 + 48 + 5 = 181 = 80 mod 101
+        assert_eq!(ring.evaluate(&p, &4), 80);
+        assert_eq!(ring.derivative(&p), ring.polynomial(vec![0, 6, 6]));
+        assert_eq!(ring.derivative(&ring.constant(9)), ring.polynomial(vec![]));
+
+        // p(x + 1) evaluated at x agrees with p at x + 1
+        let q = ring.polynomial(vec![1, 1]);
+        let composed = ring.compose(&p, &q);
+        for x in 0..101 {
+            assert_eq!(ring.evaluate(&composed, &x), ring.evaluate(&p, &((x + 1) % 101)));
+        }
+        assert_eq!(ring.compose(&q, &q), ring.polynomial(vec![2, 1]));
+    }
+
+    #[test]
+    fn run_division_and_gcd() {
+        let field = PrimeField::new(13u64);
+        let ring = PolynomialRing::new(&field);
+        let p = |c: Vec<u64>| ring.polynomial(c);
+        let linear = |r: u64| p(vec![13 - r, 1]);
+
+        // a = bq + r with deg r < deg b
+        let a = p(vec![3, 1, 4, 1, 5, 9, 2, 6]);
+        let b = p(vec![5, 3, 5]);
+        let (q, r) = ring.div_rem(&a, &b).unwrap();
+        assert!(r.degree() < b.degree());
+        assert_eq!(ring.add(ring.multiply(b.clone(), q), r), a);
+
+        let (q, r) = ring.div_rem(&b, &a).unwrap();
+        assert!(q.is_zero());
+        assert_eq!(r, b);
+        assert_eq!(ring.div_rem(&a, &p(vec![])), Err(Error::DivisionByZero));
+
+        // gcd((x - 1)(x - 2), 3(x - 1)(x - 3)) = x - 1
+        let f = ring.multiply(linear(1), linear(2));
+        let g = ring.multiply(ring.constant(3), ring.multiply(linear(1), linear(3)));
+        assert_eq!(ring.gcd(&f, &g), linear(1));
+        assert_eq!(ring.gcd(&linear(2), &linear(3)), p(vec![1]));
+        assert_eq!(ring.gcd(&p(vec![]), &g), ring.monic(&g));
+
+        // Fp works as the coefficient field too
+        type F5 = Fp<5>;
+        let ring = PolynomialRing::new(&F5::ZERO);
+        let f = ring.polynomial(vec![F5::new(4), F5::new(0), F5::new(1)]);
+        // x^2 + 4 = x^2 - 1 = (x - 1)(x + 1)
+        let (q, r) = ring.div_rem(&f, &ring.polynomial(vec![F5::new(4), F5::new(1)])).unwrap();
+        assert_eq!(q, ring.polynomial(vec![F5::new(1), F5::new(1)]));
+        assert!(r.is_zero());
+    }
+
+    #[test]
+    fn run_polynomial_ring_axioms() {
+        let rng = SystemRandom::new();
+        let random_coefficients = |n: u64| {
+            let mut bytes = [0u8; 6];
+            rng.fill(&mut bytes).unwrap();
+            let degree = (bytes[0] % 5) as usize;
+            bytes[1..=degree].iter().map(|&b| b as u64 % n).collect::<Vec<_>>()
+        };
+
+        // polynomials over a field and over a ring with zero divisors
+        let field = PrimeField::new(7u64);
+        let ring = PolynomialRing::new(&field);
+        let mut generator = || ring.polynomial(random_coefficients(7));
+        assert_eq!(check_ring(&ring, &mut Elements::Sampled(&mut generator, 200)), Ok(()));
+
+        let z12 = NaturalNumbersModN::new(12u64);
+        let ring = PolynomialRing::new(&z12);
+        let mut generator = || ring.polynomial(random_coefficients(12));
+        assert_eq!(check_ring(&ring, &mut Elements::Sampled(&mut generator, 200)), Ok(()));
+
+        // over Z/12 the product of non-zero polynomials can vanish
+        let product = ring.multiply(ring.polynomial(vec![4, 4]), ring.polynomial(vec![3]));
+        assert!(product.is_zero());
+    }
+
+}
@@ -0,0 +1,237 @@
+use crate::math::crt::crt;
+use crate::math::euclid::gcd;
+use crate::math::factor::primes_in_range;
+use crate::math::integer::UnsignedInt;
+use crate::math::primes::{is_prime, random_below};
+use crate::math::Error;
+use ring::rand::SecureRandom;
+
+/// Candidates with a factor below this bound are rejected by the sieve before any primality test
+const SIEVE_BOUND: u32 = 2048;
+
+/// Returns a random prime with exactly the given number of bits.
+///
+/// Returns an error if bits is below 2 or wider than E.
+pub fn random_prime<E: UnsignedInt>(rng: &dyn SecureRandom, bits: usize) -> Result<E, Error> {
+    search(rng, bits, &E::one(), &E::from_u32(2), false)
+}
+
+/// Returns a random prime p with exactly the given number of bits and p = residue mod modulus.
+///
+/// The residue must be coprime to the modulus as otherwise no such prime exists, and some value
+/// of that bit length must be congruent to it.
+pub fn random_prime_congruent<E: UnsignedInt>(
+    rng: &dyn SecureRandom,
+    bits: usize,
+    residue: &E,
+    modulus: &E,
+) -> Result<E, Error> {
+    if modulus.is_zero() {
+        return Err(Error::ZeroModulus);
+    }
+    if !gcd(residue.clone(), modulus.clone()).is_one() {
+        return Err(Error::NotCoprime);
+    }
+    search(rng, bits, residue, modulus, false)
+}
+
+/// Returns a random safe prime p = 2q + 1 where q is also prime, with exactly the given number
+/// of bits. The multiplicative group mod a safe prime has a subgroup of large prime order q which
+/// suits Diffie-Hellman.
+///
+/// Returns an error if bits is below 4 or wider than E.
+pub fn random_safe_prime<E: UnsignedInt>(rng: &dyn SecureRandom, bits: usize) -> Result<E, Error> {
+    if bits < 4 {
+        return Err(Error::InvalidBitLength);
+    }
+    // above 3 the prime q is odd and 2 mod 3 so p = 2q + 1 is 11 mod 12
+    search(rng, bits, &E::from_u32(11), &E::from_u32(12), true)
+}
+
+/// Draws random starting points and walks up through the odd values congruent to residue mod
+/// modulus until one passes the sieve and the primality tests.
+///
+/// The sieve tracks each candidate mod the small primes so moving to the next candidate only
+/// updates those residues. For safe primes it also rejects p = 1 mod s as then s divides q.
+fn search<E: UnsignedInt>(rng: &dyn SecureRandom, bits: usize, residue: &E, modulus: &E, safe: bool) -> Result<E, Error> {
+    // 2^(bits - 1) is built by doubling so it wraps to zero rather than overflowing when bits
+    // is wider than E
+    let low = (1..bits).fold(E::one(), |acc, _| acc << 1);
+    if bits < 2 || low.is_zero() {
+        return Err(Error::InvalidBitLength);
+    }
+
+    let (residue, step) = crt(&[(residue.clone() % modulus.clone(), modulus.clone()), (E::one(), E::from_u32(2))])?;
+    // the first congruent value must have the requested number of bits
+    if residue.sub_mod(&low, &step) >= low {
+        return Err(Error::InvalidBitLength);
+    }
+
+    // a small prime may only be sieved out when it is below the candidate, or below q for safe primes
+    let smallest = if safe { low.clone() >> 1 } else { low.clone() };
+    let sieve: Vec<u32> = primes_in_range(3, SIEVE_BOUND as u64)
+        .into_iter()
+        .map(|s| s as u32)
+        .filter(|&s| E::from_u32(s) < smallest)
+        .collect();
+    let step_residues: Vec<u32> = sieve.iter().map(|&s| small_residue(&step, s)).collect();
+
+    loop {
+        // the distance from the candidate to 2^bits - 1, the largest value with the requested bits
+        let room = |c: &E| (low.clone() - E::one()) - (c.clone() - low.clone());
+
+        let start = low.clone() + random_below(rng, &low);
+        let offset = residue.sub_mod(&start, &step);
+        if room(&start) < offset {
+            continue;
+        }
+        let mut candidate = start + offset;
+        let mut residues: Vec<u32> = sieve.iter().map(|&s| small_residue(&candidate, s)).collect();
+
+        loop {
+            let sieved = residues.iter().all(|&r| r != 0 && !(safe && r == 1));
+            if sieved && (!safe || is_prime(&(candidate.clone() >> 1))) && is_prime(&candidate) {
+                return Ok(candidate);
+            }
+
+            if room(&candidate) < step {
+                break;
+            }
+            candidate = candidate + step.clone();
+            for ((r, &s), &step_r) in residues.iter_mut().zip(&sieve).zip(&step_residues) {
+                *r = (*r + step_r) % s;
+            }
+        }
+    }
+}
+
+/// Returns n mod s
+fn small_residue<E: UnsignedInt>(n: &E, s: u32) -> u32 {
+    (n.clone() % E::from_u32(s)).to_u64().unwrap() as u32
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::math::crt::CrtIsomorphism;
+    use crate::math::primes::is_prime_u64;
+    use crate::math::BigUint;
+    use ring::rand::SystemRandom;
+
+    #[test]
+    fn run_random_prime() {
+        let rng = SystemRandom::new();
+
+        for bits in 2..=32 {
+            let p: u32 = random_prime(&rng, bits).unwrap();
+            assert_eq!(p.bits(), bits);
+            assert!(is_prime_u64(p as u64), "p = {}", p);
+        }
+
+        let p: u64 = random_prime(&rng, 64).unwrap();
+        assert_eq!(p.bits(), 64);
+        assert!(is_prime_u64(p));
+
+        let p: u128 = random_prime(&rng, 128).unwrap();
+        assert_eq!(p.bits(), 128);
+        assert!(is_prime(&p));
+
+        let p: BigUint = random_prime(&rng, 256).unwrap();
+        assert_eq!(p.bits(), 256);
+        assert!(is_prime(&p));
+
+        assert_eq!(random_prime::<u32>(&rng, 1), Err(Error::InvalidBitLength));
+        assert_eq!(random_prime::<u32>(&rng, 33), Err(Error::InvalidBitLength));
+    }
+
+    #[test]
+    fn run_random_prime_congruent() {
+        let rng = SystemRandom::new();
+
+        // primes p = 1 mod 2^16 have the roots of unity needed for a number theoretic transform
+        let p: u64 = random_prime_congruent(&rng, 48, &1, &(1 << 16)).unwrap();
+        assert_eq!(p.bits(), 48);
+        assert_eq!(p % (1 << 16), 1);
+        assert!(is_prime_u64(p));
+
+        // an odd modulus still gives odd primes
+        for _ in 0..20 {
+            let p: u32 = random_prime_congruent(&rng, 20, &2, &3).unwrap();
+            assert_eq!(p % 3, 2);
+            assert!(is_prime_u64(p as u64));
+        }
+
+        let p: u64 = random_prime_congruent(&rng, 64, &3, &4).unwrap();
+        assert_eq!(p % 4, 3);
+        assert!(is_prime_u64(p));
+
+        assert_eq!(random_prime_congruent(&rng, 32, &2u32, &4), Err(Error::NotCoprime));
+        assert_eq!(random_prime_congruent(&rng, 32, &1u32, &0), Err(Error::ZeroModulus));
+        assert_eq!(random_prime_congruent(&rng, 8, &301u32, &1000), Err(Error::InvalidBitLength));
+    }
+
+    #[test]
+    fn run_random_safe_prime() {
+        let rng = SystemRandom::new();
+
+        for bits in 4..=32 {
+            let p: u64 = random_safe_prime(&rng, bits).unwrap();
+            assert_eq!(p.bits(), bits);
+            assert!(is_prime_u64(p) && is_prime_u64(p / 2), "p = {}", p);
+        }
+
+        let p: u64 = random_safe_prime(&rng, 64).unwrap();
+        assert!(is_prime_u64(p) && is_prime_u64(p / 2));
+
+        assert_eq!(random_safe_prime::<u32>(&rng, 3), Err(Error::InvalidBitLength));
+    }
+
+    #[test]
+    fn run_diffie_hellman_over_a_safe_prime() {
+        let rng = SystemRandom::new();
+        let p: u64 = random_safe_prime(&rng, 62).unwrap();
+        let q = p / 2;
+
+        // every square other than one generates the subgroup of prime order q
+        let g = 4u64;
+        assert_eq!(g.pow_mod(&q, &p), 1);
+
+        let a = random_below(&rng, &q);
+        let b = random_below(&rng, &q);
+        let (big_a, big_b) = (g.pow_mod(&a, &p), g.pow_mod(&b, &p));
+        assert_eq!(big_b.pow_mod(&a, &p), big_a.pow_mod(&b, &p));
+    }
+
+    #[test]
+    fn run_rsa_key_generation() {
+        let rng = SystemRandom::new();
+        let e = 65537u64;
+
+        // p - 1 and q - 1 must be coprime to e for the decryption exponent to exist
+        let rsa_prime = || loop {
+            let p: u64 = random_prime(&rng, 32).unwrap();
+            if gcd(p - 1, e) == 1 {
+                return p;
+            }
+        };
+        let p = rsa_prime();
+        let q = loop {
+            let q = rsa_prime();
+            if q != p {
+                break q;
+            }
+        };
+
+        let n = p * q;
+        let d = crate::math::euclid::mod_inverse(e, (p - 1) * (q - 1)).unwrap();
+        let message = 0x0123_4567_89ab_cdef % n;
+        let ciphertext = message.pow_mod(&e, &n);
+        assert_eq!(ciphertext.pow_mod(&d, &n), message);
+
+        let iso = CrtIsomorphism::new(p, q).unwrap();
+        let (cp, cq) = iso.forward(ciphertext);
+        assert_eq!(iso.backward((cp.pow_mod(&d, &p), cq.pow_mod(&d, &q))), message);
+    }
+
+}
@@ -0,0 +1,351 @@
+use crate::math::integer::UnsignedInt;
+use crate::math::number_theory::{isqrt, jacobi};
+use ring::rand::{SecureRandom, SystemRandom};
+
+/// The error bound used by is_prime, a composite passes with probability below 2^-128
+pub const DEFAULT_ERROR_BITS: u32 = 128;
+
+/// The odd primes below 100 which are used to quickly reject most composites
+const SMALL_PRIMES: [u32; 24] = [3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97];
+
+/// Returns true if n is prime. Values which fit in a u64 are checked exactly, wider values are
+/// probable primes with an error bound of 2^-DEFAULT_ERROR_BITS.
+pub fn is_prime<E: UnsignedInt>(n: &E) -> bool {
+    is_probable_prime(n, DEFAULT_ERROR_BITS)
+}
+
+/// Returns true if n is prime with the probability of a composite passing below 2^-error_bits.
+///
+/// Values which fit in a u64 are checked exactly. Wider values must pass the Baillie-PSW test,
+/// which has no known counterexample, and then enough Miller-Rabin rounds with random bases to
+/// meet the error bound on their own as each round passes a composite with probability at most 1/4.
+pub fn is_probable_prime<E: UnsignedInt>(n: &E, error_bits: u32) -> bool {
+    if let Some(n) = n.to_u64() {
+        return is_prime_u64(n);
+    }
+    baillie_psw(n) && miller_rabin(n, error_bits.div_ceil(2) as usize)
+}
+
+/// Runs the given number of Miller-Rabin rounds with random bases returning false as soon as
+/// one proves n composite
+pub fn miller_rabin<E: UnsignedInt>(n: &E, rounds: usize) -> bool {
+    let three = E::from_u32(3);
+    if *n <= three {
+        return *n > E::one();
+    }
+    if n.is_even() {
+        return false;
+    }
+
+    // bases are drawn from 2..=n-2
+    let rng = SystemRandom::new();
+    let range = n.clone() - three;
+    (0..rounds).all(|_| {
+        let base = random_below(&rng, &range) + E::from_u32(2);
+        is_strong_probable_prime(n, &base)
+    })
+}
+
+/// The Baillie-PSW test combines a Miller-Rabin round with base 2 and a strong Lucas test.
+///
+/// The two tests fail on very different composites and no number is known to pass both.
+pub fn baillie_psw<E: UnsignedInt>(n: &E) -> bool {
+    let two = E::from_u32(2);
+    if *n < two {
+        return false;
+    }
+    if n.is_even() {
+        return *n == two;
+    }
+    for p in SMALL_PRIMES {
+        let p = E::from_u32(p);
+        if *n == p {
+            return true;
+        }
+        if (n.clone() % p).is_zero() {
+            return false;
+        }
+    }
+
+    is_strong_probable_prime(n, &two) && is_strong_lucas_probable_prime(n)
+}
+
+/// Returns a uniformly random value in 0..bound, which must not be zero.
+///
+/// Values with as many bits as the bound are drawn until one is below it, which takes fewer than
+/// two draws on average. Reducing a wider value instead would favour the low residues whenever
+/// it cannot be wider than the element type.
+pub fn random_below<E: UnsignedInt>(rng: &dyn SecureRandom, bound: &E) -> E {
+    assert!(!bound.is_zero(), "bound is zero");
+    let bits = bound.bits();
+    let mut bytes = vec![0u8; bits.div_ceil(8)];
+    loop {
+        rng.fill(&mut bytes).expect("failed to generate random bytes");
+        // clear the bits above the bound's top bit
+        bytes[0] &= 0xff >> (8 * bytes.len() - bits);
+        let value = bytes.iter().fold(E::zero(), |acc, &b| (acc << 8) + E::from_u32(b as u32));
+        if value < *bound {
+            return value;
+        }
+    }
+}
+
+/// Checks whether odd n > 2 is a strong probable prime to the given base. Writing
+/// n - 1 = d 2^s with d odd, a prime satisfies either base^d = 1 or base^(d 2^r) = -1 for some r < s.
+fn is_strong_probable_prime<E: UnsignedInt>(n: &E, base: &E) -> bool {
+    let n_minus_one = n.clone() - E::one();
+    let s = n_minus_one.trailing_zeros();
+    let d = n_minus_one.clone() >> s;
+
+    let mut x = base.pow_mod(&d, n);
+    if x.is_one() || x == n_minus_one {
+        return true;
+    }
+    for _ in 1..s {
+        x = x.mul_mod(&x, n);
+        if x == n_minus_one {
+            return true;
+        }
+    }
+    false
+}
+
+/// The strong Lucas probable prime test for odd n using Selfridge's parameters: D is the first of
+/// 5, -7, 9, -11, ... with Jacobi symbol (D/n) = -1, P = 1 and Q = (1 - D) / 4.
+///
+/// Writing n + 1 = d 2^s with d odd, a prime satisfies either U_d = 0 or V_(d 2^r) = 0 for some r < s.
+fn is_strong_lucas_probable_prime<E: UnsignedInt>(n: &E) -> bool {
+    // a square never has (D/n) = -1 so the search for D would not end
+    let root = isqrt(n);
+    if root.clone() * root == *n {
+        return false;
+    }
+
+    // D and Q are kept as residues mod n
+    let mut magnitude = 5u32;
+    let mut negative = false;
+    let d = loop {
+        let m = E::from_u32(magnitude);
+        let d = if negative { E::zero().sub_mod(&m, n) } else { m % n.clone() };
+        match jacobi(&d, n) {
+            -1 => break d,
+            // D shares a factor with n
+            0 if E::from_u32(magnitude) < *n => return false,
+            _ => {}
+        }
+        magnitude += 2;
+        negative = !negative;
+    };
+
+    // halving is multiplying by the inverse of 2 which for odd x is (x + n) / 2 = x / 2 + (n + 1) / 2
+    let half_n_plus_one = (n.clone() >> 1) + E::one();
+    let quarter = half_n_plus_one.mul_mod(&half_n_plus_one, n);
+    let q = E::one().sub_mod(&d, n).mul_mod(&quarter, n);
+    let half = |x: E| {
+        if x.is_even() {
+            x >> 1
+        } else {
+            (x >> 1) + half_n_plus_one.clone()
+        }
+    };
+
+    let n_plus_one = n.clone() + E::one();
+    let s = n_plus_one.trailing_zeros();
+    let k = n_plus_one >> s;
+
+    // walk the bits of k from the top using U_2k = U_k V_k, V_2k = V_k^2 - 2Q^k,
+    // U_(k+1) = (P U_k + V_k) / 2 and V_(k+1) = (D U_k + P V_k) / 2
+    let (mut u, mut v, mut q_k) = (E::one(), E::one(), q.clone());
+    for i in (0..k.bits() - 1).rev() {
+        u = u.mul_mod(&v, n);
+        v = v.mul_mod(&v, n).sub_mod(&q_k.add_mod(&q_k, n), n);
+        q_k = q_k.mul_mod(&q_k, n);
+        if k.bit(i) {
+            let next_u = half(u.add_mod(&v, n));
+            v = half(d.mul_mod(&u, n).add_mod(&v, n));
+            u = next_u;
+            q_k = q_k.mul_mod(&q, n);
+        }
+    }
+
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+    for _ in 1..s {
+        v = v.mul_mod(&v, n).sub_mod(&q_k.add_mod(&q_k, n), n);
+        q_k = q_k.mul_mod(&q_k, n);
+        if v.is_zero() {
+            return true;
+        }
+    }
+    false
+}
+
+/// Deterministic Miller-Rabin test which is exact for every u64 using the first twelve primes
+/// as witnesses. Written as a const fn so it can check the modulus of Fp at compile time.
+pub const fn is_prime_u64(n: u64) -> bool {
+    const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    if n < 2 {
+        return false;
+    }
+    let mut i = 0;
+    while i < WITNESSES.len() {
+        if n == WITNESSES[i] {
+            return true;
+        }
+        if n.is_multiple_of(WITNESSES[i]) {
+            return false;
+        }
+        i += 1;
+    }
+
+    // write n - 1 = d * 2^s with d odd
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+
+    let mut i = 0;
+    while i < WITNESSES.len() {
+        let mut x = const_pow_mod(WITNESSES[i], d, n);
+        if x != 1 && x != n - 1 {
+            let mut r = 1;
+            while r < s && x != n - 1 {
+                x = const_mul_mod(x, x, n);
+                r += 1;
+            }
+            if x != n - 1 {
+                return false;
+            }
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn const_mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+const fn const_pow_mod(mut base: u64, mut exponent: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    base %= m;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = const_mul_mod(result, base, m);
+        }
+        base = const_mul_mod(base, base, m);
+        exponent >>= 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::math::BigUint;
+
+    /// Returns a table of which numbers below n are prime using the sieve of Eratosthenes
+    fn sieve(n: usize) -> Vec<bool> {
+        let mut is_prime = vec![true; n];
+        is_prime[0] = false;
+        is_prime[1] = false;
+        for i in 2..n {
+            if is_prime[i] {
+                (i * i..n).step_by(i).for_each(|j| is_prime[j] = false);
+            }
+        }
+        is_prime
+    }
+
+    #[test]
+    fn run_is_prime_u64() {
+        for (n, &expected) in sieve(100_000).iter().enumerate() {
+            assert_eq!(is_prime_u64(n as u64), expected, "n = {}", n);
+        }
+
+        // strong pseudoprimes to several small bases and the largest primes below 2^32 and 2^64
+        assert!(!is_prime_u64(3_215_031_751));
+        assert!(!is_prime_u64(3_825_123_056_546_413_051));
+        assert!(is_prime_u64(u32::MAX as u64 - 4));
+        assert!(is_prime_u64(u64::MAX - 58));
+        assert!(!is_prime_u64(u64::MAX));
+    }
+
+    #[test]
+    fn run_baillie_psw() {
+        for (n, &expected) in sieve(20_000).iter().enumerate() {
+            assert_eq!(baillie_psw(&(n as u64)), expected, "n = {}", n);
+        }
+
+        // strong pseudoprimes to base 2 are caught by the Lucas test
+        for n in [2047u64, 3277, 4033, 4681, 8321, 3_215_031_751] {
+            assert!(is_strong_probable_prime(&n, &2));
+            assert!(!baillie_psw(&n), "n = {}", n);
+        }
+
+        // strong Lucas pseudoprimes are caught by the base 2 test
+        for n in [5459u64, 5777, 10877, 16109, 18971] {
+            assert!(is_strong_lucas_probable_prime(&n));
+            assert!(!baillie_psw(&n), "n = {}", n);
+        }
+
+        // Carmichael numbers and squares of primes
+        for n in [561u64, 1105, 1729, 41041, 825_265, 65521 * 65521] {
+            assert!(!baillie_psw(&n), "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn run_is_prime_wide() {
+        // the largest prime below 2^128 and the Mersenne primes 2^89 - 1 and 2^127 - 1
+        assert!(is_prime(&(u128::MAX - 158)));
+        assert!(is_prime(&((1u128 << 89) - 1)));
+        assert!(is_prime(&((1u128 << 127) - 1)));
+        assert!(!is_prime(&u128::MAX));
+        assert!(!is_prime(&((1u128 << 67) - 1)));
+        assert!(!is_prime(&(18_446_744_073_709_551_557u128 * 18_446_744_073_709_551_533)));
+
+        // the P-256 field prime and the composite Fermat number 2^256 + 1
+        let p = BigUint::from_hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff").unwrap();
+        assert!(is_prime(&p));
+        assert!(!is_prime(&((BigUint::one() << 256usize) + BigUint::one())));
+        assert!(!is_prime(&(&p * &p)));
+
+        // the error bound only changes the number of random rounds
+        assert!(is_probable_prime(&p, 2));
+        assert!(miller_rabin(&p, 8));
+        assert!(!miller_rabin(&(BigUint::from(u64::MAX - 58) * BigUint::from(u32::MAX - 4)), 8));
+    }
+
+    #[test]
+    fn run_random_below() {
+        let rng = SystemRandom::new();
+        for bound in [1u32, 2, 3, 1000, u32::MAX] {
+            for _ in 0..100 {
+                assert!(random_below(&rng, &bound) < bound);
+            }
+        }
+        let bound = BigUint::from_hex("123456789abcdef0123456789abcdef").unwrap();
+        assert!(random_below(&rng, &bound) < bound);
+        assert_eq!(random_below(&rng, &1u64), 0);
+    }
+
+    #[test]
+    fn run_random_below_is_uniform() {
+        // a third of 0..3 2^30 is below 2^30, where reducing 32 random bits would give a half
+        let rng = SystemRandom::new();
+        let bound = 3u32 << 30;
+        let samples = 30000;
+        let low = (0..samples).filter(|_| random_below(&rng, &bound) < 1 << 30).count();
+        assert!((9000..11000).contains(&low), "{} of {} below 2^30", low, samples);
+
+        // every value of a small bound turns up about equally often
+        let mut counts = [0u32; 6];
+        for _ in 0..6000 {
+            counts[random_below(&rng, &6u64) as usize] += 1;
+        }
+        assert!(counts.iter().all(|&c| (800..1200).contains(&c)), "{:?}", counts);
+    }
+
+}
@@ -1,4 +1,7 @@
 use crate::math::group::{AbelianGroup, Addition, Group, Monoid, Multiplication};
+use crate::math::euclid;
+use crate::math::integer::UnsignedInt;
+use crate::math::Error;
 
 /// A Ring is an abelian group over addition, a monoid under multiplication and distributive
 /// for multiplication with respect to addition.
@@ -11,63 +14,124 @@ use crate::math::group::{AbelianGroup, Addition, Group, Monoid, Multiplication};
 /// Multiplication is distributive with respect to addition:
 /// Left distributive - a x (b + c) = (a x b) + (a x c)
 /// Right distributive - (b + c) x a = (b x a) + (c x a)
-trait Ring<E>: AbelianGroup<Addition, E> + Monoid<Multiplication, E> {
+pub trait Ring<E>: AbelianGroup<Addition, E> + Monoid<Multiplication, E> {
     /// Supports addition
-    fn add(&self, e1: E, e2: E) -> E;
+    fn add(&self, e1: E, e2: E) -> E {
+        <Self as Monoid<Addition, E>>::apply(self, e1, e2)
+    }
     /// Supports subtraction which is the same as adding the inverse
-    fn subtract(&self, e1: E, e2: E) -> E;
+    fn subtract(&self, e1: E, e2: E) -> E {
+        let inverse = <Self as Group<Addition, E>>::inverse(self, e2);
+        self.add(e1, inverse)
+    }
     /// Supports multiplication
-    fn multiply(&self, e1: E, e2: E) -> E;
+    fn multiply(&self, e1: E, e2: E) -> E {
+        <Self as Monoid<Multiplication, E>>::apply(self, e1, e2)
+    }
+    /// Returns the coefficients of the product of two polynomials given by their coefficients.
+    /// Rings with a faster method than multiplying every pair can override this.
+    fn convolve(&self, a: &[E], b: &[E]) -> Vec<E>
+    where
+        Self: Sized,
+        E: Clone,
+    {
+        schoolbook_convolve(self, a, b)
+    }
+}
+
+/// Multiplies every pair of coefficients, the schoolbook method
+pub fn schoolbook_convolve<R: Ring<E>, E: Clone>(ring: &R, a: &[E], b: &[E]) -> Vec<E> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let zero = <R as Monoid<Addition, E>>::identity(ring);
+    let mut c = vec![zero; a.len() + b.len() - 1];
+    for (i, a_i) in a.iter().enumerate() {
+        for (j, b_j) in b.iter().enumerate() {
+            let product = ring.multiply(a_i.clone(), b_j.clone());
+            c[i + j] = ring.add(c[i + j].clone(), product);
+        }
+    }
+    c
 }
 
 
 /// The natural numbers mod n form a ring.
-struct NaturalNumbersModN(u32);
+///
+/// As with PrimeField the element type E sets the width of the modulus and all arithmetic
+/// is reduced without overflowing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NaturalNumbersModN<E>(E);
+
+impl<E: UnsignedInt> NaturalNumbersModN<E> {
+
+    pub fn new(modulus: E) -> Self {
+        match NaturalNumbersModN::try_new(modulus) {
+            Ok(ring) => ring,
+            Err(e) => panic!("{}", e),
+        }
+    }
 
-impl AbelianGroup<Addition, u32> for NaturalNumbersModN {}
+    /// Returns an error rather than panicking if the modulus is zero
+    pub fn try_new(modulus: E) -> Result<Self, Error> {
+        if modulus.is_zero() {
+            return Err(Error::ZeroModulus);
+        }
 
-impl Group<Addition, u32> for NaturalNumbersModN {
-    fn apply(&self, e1: u32, e2: u32) -> u32 {
-        (e1 + e2) % self.0
+        Ok(NaturalNumbersModN(modulus))
     }
 
-    fn identity(&self) -> u32 {
-        0
+    /// Returns the multiplicative inverse of e when it is a unit, that is when e is coprime to n.
+    /// Multiplication only forms a monoid so other elements have no inverse.
+    pub fn unit_inverse(&self, e: E) -> Result<E, Error> {
+        if (e.clone() % self.0.clone()).is_zero() && !self.0.is_one() {
+            return Err(Error::DivisionByZero);
+        }
+
+        euclid::mod_inverse(e, self.0.clone()).ok_or(Error::NotInvertible)
     }
 
-    fn inverse(&self, e: u32) -> u32 {
-        self.0 - e % self.0
+    /// Returns the modulus n
+    pub fn modulus(&self) -> &E {
+        &self.0
     }
 }
 
-impl Monoid<Multiplication, u32> for NaturalNumbersModN {
-    fn apply(&self, e1: u32, e2: u32) -> u32 {
-        (e1 * e2) % self.0
+impl<E: UnsignedInt> AbelianGroup<Addition, E> for NaturalNumbersModN<E> {}
+
+impl<E: UnsignedInt> Monoid<Addition, E> for NaturalNumbersModN<E> {
+    fn apply(&self, e1: E, e2: E) -> E {
+        e1.add_mod(&e2, &self.0)
     }
 
-    fn identity(&self) -> u32 {
-        1
+    fn identity(&self) -> E {
+        E::zero()
     }
 }
 
-impl Ring<u32> for NaturalNumbersModN {
-    fn add(&self, e1: u32, e2: u32) -> u32 {
-        <dyn AbelianGroup<Addition, u32>>::apply(self, e1, e2)
+impl<E: UnsignedInt> Group<Addition, E> for NaturalNumbersModN<E> {
+    fn inverse(&self, e: E) -> E {
+        E::zero().sub_mod(&e, &self.0)
     }
+}
 
-    fn subtract(&self, e1: u32, e2: u32) -> u32 {
-        <dyn AbelianGroup<Addition, u32>>::apply(self, e1, self.inverse(e2))
+impl<E: UnsignedInt> Monoid<Multiplication, E> for NaturalNumbersModN<E> {
+    fn apply(&self, e1: E, e2: E) -> E {
+        e1.mul_mod(&e2, &self.0)
     }
 
-    fn multiply(&self, e1: u32, e2: u32) -> u32 {
-        Monoid::apply(self, e1, e2)
+    fn identity(&self) -> E {
+        E::one() % self.0.clone()
     }
 }
 
+impl<E: UnsignedInt> Ring<E> for NaturalNumbersModN<E> {}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::math::BigUint;
 
     #[test]
     fn run() {
@@ -76,7 +140,7 @@ mod tests {
 
     #[test]
     fn run_natural_numbers_modn() {
-        let ring = NaturalNumbersModN(4);
+        let ring = NaturalNumbersModN::new(4u32);
 
         let result = ring.add(3, 2);
         assert_eq!(1, result);
@@ -86,6 +150,74 @@ mod tests {
 
         let result = ring.multiply(2, 3);
         assert_eq!(2, result);
+
+        // the additive inverse of zero is zero rather than the modulus
+        assert_eq!(0, ring.inverse(0));
+    }
+
+    #[test]
+    fn run_natural_numbers_modn_fallible() {
+        assert_eq!(NaturalNumbersModN::try_new(0u32).err(), Some(Error::ZeroModulus));
+
+        let ring = NaturalNumbersModN::try_new(12u32).unwrap();
+        assert_eq!(ring.unit_inverse(5), Ok(5));
+        assert_eq!(ring.unit_inverse(7), Ok(7));
+        assert_eq!(ring.unit_inverse(0), Err(Error::DivisionByZero));
+        assert_eq!(ring.unit_inverse(8), Err(Error::NotInvertible));
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus is zero")]
+    fn run_natural_numbers_modn_zero_modulus() {
+        NaturalNumbersModN::new(0u64);
+    }
+
+    /// Checks the ring axioms for every pair and triple of elements and compares the
+    /// results with BigUint arithmetic which cannot overflow
+    fn check_ring_axioms<E: UnsignedInt + Copy + Into<BigUint>>(ring: &NaturalNumbersModN<E>, elements: &[E]) {
+        let n: BigUint = (*ring.modulus()).into();
+        let zero = E::zero();
+        let one = E::one();
+
+        for &a in elements {
+            assert_eq!(ring.add(a, zero), a);
+            assert_eq!(ring.multiply(a, one), a);
+            assert_eq!(ring.add(a, ring.inverse(a)), zero);
+
+            for &b in elements {
+                let (big_a, big_b): (BigUint, BigUint) = (a.into(), b.into());
+                assert_eq!(ring.add(a, b).into(), (&big_a + &big_b) % &n);
+                assert_eq!(ring.multiply(a, b).into(), (&big_a * &big_b) % &n);
+                assert_eq!(ring.add(ring.subtract(a, b), b), a);
+                assert_eq!(ring.add(a, b), ring.add(b, a));
+
+                for &c in elements {
+                    assert_eq!(ring.add(a, ring.add(b, c)), ring.add(ring.add(a, b), c));
+                    assert_eq!(ring.multiply(a, ring.multiply(b, c)), ring.multiply(ring.multiply(a, b), c));
+                    assert_eq!(ring.multiply(a, ring.add(b, c)), ring.add(ring.multiply(a, b), ring.multiply(a, c)));
+                    assert_eq!(ring.multiply(ring.add(b, c), a), ring.add(ring.multiply(b, a), ring.multiply(c, a)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn run_natural_numbers_modn_axioms_small() {
+        let ring = NaturalNumbersModN::new(12u32);
+        let elements: Vec<u32> = (0..12).collect();
+        check_ring_axioms(&ring, &elements);
+    }
+
+    #[test]
+    fn run_natural_numbers_modn_axioms_near_the_top_of_each_width() {
+        let n = u32::MAX;
+        check_ring_axioms(&NaturalNumbersModN::new(n), &[0, 1, 2, n / 2, n / 2 + 1, n - 2, n - 1]);
+
+        let n = u64::MAX;
+        check_ring_axioms(&NaturalNumbersModN::new(n), &[0, 1, 2, n / 2, n / 2 + 1, n - 2, n - 1]);
+
+        let n = u128::MAX;
+        check_ring_axioms(&NaturalNumbersModN::new(n), &[0, 1, 2, n / 2, n / 2 + 1, n - 2, n - 1]);
     }
 
 }
@@ -0,0 +1,234 @@
+use crate::math::dlog::pohlig_hellman;
+use crate::math::euclid;
+use crate::math::field::{Field, PrimeField};
+use crate::math::integer::UnsignedInt;
+use crate::math::number_theory::legendre;
+use crate::math::units::UnitsModN;
+
+/// Square and cube roots in a field
+pub trait Roots<E>: Field<E> {
+    /// Returns true if e has a square root
+    fn is_square(&self, e: &E) -> bool;
+
+    /// Returns a square root of e, the other is its negation, or None if e is not a square
+    fn sqrt(&self, e: E) -> Option<E>;
+
+    /// Returns a cube root of e or None if e is not a cube
+    fn cbrt(&self, e: E) -> Option<E>;
+}
+
+impl<E: UnsignedInt> Roots<E> for PrimeField<E> {
+    fn is_square(&self, e: &E) -> bool {
+        legendre(e, self.modulus()) >= 0
+    }
+
+    fn sqrt(&self, e: E) -> Option<E> {
+        sqrt_mod_prime(&e, self.modulus())
+    }
+
+    fn cbrt(&self, e: E) -> Option<E> {
+        cbrt_mod_prime(&e, self.modulus())
+    }
+}
+
+/// Returns the smaller of the two square roots of a mod the prime p, or None if a is not a square.
+///
+/// When p = 3 mod 4 the root is a^((p+1)/4) as its square a^((p+1)/2) = a a^((p-1)/2) = a.
+/// Otherwise the Tonelli-Shanks algorithm is used.
+pub fn sqrt_mod_prime<E: UnsignedInt>(a: &E, p: &E) -> Option<E> {
+    let a = a.clone() % p.clone();
+    if a.is_zero() || *p == E::from_u32(2) {
+        return Some(a);
+    }
+    if legendre(&a, p) != 1 {
+        return None;
+    }
+
+    let root = if p.bit(1) {
+        a.pow_mod(&((p.clone() + E::one()) >> 2), p)
+    } else {
+        tonelli_shanks(&a, p)
+    };
+    let negated = p.clone() - root.clone();
+    Some(root.min(negated))
+}
+
+/// Tonelli-Shanks for a square a mod an odd prime p.
+///
+/// Writing p - 1 = q 2^s with q odd, r = a^((q+1)/2) satisfies r^2 = a t with t = a^q in the
+/// subgroup of order 2^s. Each step multiplies r by a power of a non-residue to lower the order
+/// of t until t = 1.
+fn tonelli_shanks<E: UnsignedInt>(a: &E, p: &E) -> E {
+    let p_minus_one = p.clone() - E::one();
+    let s = p_minus_one.trailing_zeros();
+    let q = p_minus_one >> s;
+
+    // half of the residues are non-residues so this search is short
+    let mut z = E::from_u32(2);
+    while legendre(&z, p) != -1 {
+        z = z + E::one();
+    }
+
+    let mut m = s;
+    let mut c = z.pow_mod(&q, p);
+    let mut t = a.pow_mod(&q, p);
+    let mut r = a.pow_mod(&((q + E::one()) >> 1), p);
+    while !t.is_one() {
+        // the least i with t^(2^i) = 1
+        let mut i = 0;
+        let mut t_2i = t.clone();
+        while !t_2i.is_one() {
+            t_2i = t_2i.mul_mod(&t_2i, p);
+            i += 1;
+        }
+
+        let b = (0..m - i - 1).fold(c, |b, _| b.mul_mod(&b, p));
+        m = i;
+        c = b.mul_mod(&b, p);
+        t = t.mul_mod(&c, p);
+        r = r.mul_mod(&b, p);
+    }
+    r
+}
+
+/// Returns a cube root of a mod the prime p or None if a is not a cube.
+///
+/// When p = 2 mod 3 cubing is a bijection so the root is unique and a^((2p-1)/3) inverts it.
+/// When p = 1 mod 3 a cube has three roots and one is found by reducing to a discrete
+/// logarithm in the subgroup of order 3^s dividing p - 1, which Pohlig-Hellman solves quickly.
+pub fn cbrt_mod_prime<E: UnsignedInt>(a: &E, p: &E) -> Option<E> {
+    let a = a.clone() % p.clone();
+    let three = E::from_u32(3);
+    if a.is_zero() || *p <= three {
+        return Some(a);
+    }
+
+    let p_minus_one = p.clone() - E::one();
+    if !(p_minus_one.clone() % three.clone()).is_zero() {
+        // (2p - 1) / 3 computed as 2((p - 2) / 3) + 1 so it cannot overflow
+        let exponent = ((p.clone() - E::from_u32(2)) / three) * E::from_u32(2) + E::one();
+        return Some(a.pow_mod(&exponent, p));
+    }
+    if !a.pow_mod(&(p_minus_one.clone() / three.clone()), p).is_one() {
+        return None;
+    }
+
+    // write p - 1 = 3^s t with t not divisible by 3
+    let mut t = p_minus_one.clone();
+    let mut three_s = E::one();
+    while (t.clone() % three.clone()).is_zero() {
+        t = t / three.clone();
+        three_s = three_s * three.clone();
+    }
+
+    // r = a^k with 3k = 1 mod t has r^3 = a e for e in the subgroup of order 3^s
+    let k = euclid::mod_inverse(three.clone(), t.clone()).unwrap();
+    let r = a.pow_mod(&k, p);
+    let a_inverse = euclid::mod_inverse(a.clone(), p.clone()).unwrap();
+    let e = r.mul_mod(&r, p).mul_mod(&r, p).mul_mod(&a_inverse, p);
+
+    // a non-cube z gives c = z^t which generates that subgroup, and e = c^l with 3 dividing l
+    let mut z = E::from_u32(2);
+    while z.pow_mod(&(p_minus_one.clone() / three.clone()), p).is_one() {
+        z = z + E::one();
+    }
+    let c = z.pow_mod(&t, p);
+    let l = pohlig_hellman(&UnitsModN::new(p.clone()), &c, &e, &three_s).ok()?;
+
+    // then (r c^(-l/3))^3 = a e c^-l = a
+    let c_inverse = euclid::mod_inverse(c, p.clone()).unwrap();
+    Some(r.mul_mod(&c_inverse.pow_mod(&(l / three), p), p))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::math::field::Fp;
+    use crate::math::BigUint;
+
+    #[test]
+    fn run_sqrt_small_primes_exhaustively() {
+        for p in [2u32, 3, 5, 7, 11, 13, 17, 41, 73, 97, 113, 193, 257, 769] {
+            let field = PrimeField::new(p);
+            for a in 0..p {
+                let squares: Vec<u32> = (0..p).filter(|x| x * x % p == a).collect();
+                assert_eq!(field.is_square(&a), !squares.is_empty(), "a = {}, p = {}", a, p);
+                assert_eq!(field.sqrt(a), squares.first().copied(), "a = {}, p = {}", a, p);
+            }
+        }
+    }
+
+    #[test]
+    fn run_cbrt_small_primes_exhaustively() {
+        for p in [2u32, 3, 5, 7, 11, 13, 19, 31, 37, 109, 163, 271, 487] {
+            let field = PrimeField::new(p);
+            for a in 0..p {
+                let cubes: Vec<u32> = (0..p).filter(|x| x * x % p * x % p == a).collect();
+                match field.cbrt(a) {
+                    Some(root) => assert!(cubes.contains(&root), "a = {}, p = {}", a, p),
+                    None => assert!(cubes.is_empty(), "a = {}, p = {}", a, p),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn run_roots_large_primes() {
+        // 998244353 = 119 2^23 + 1 takes many Tonelli-Shanks steps
+        let p = 998_244_353u64;
+        for x in [2u64, 3, 12345, p - 1] {
+            let root = sqrt_mod_prime(&(x * x % p), &p).unwrap();
+            assert!(root == x || root == p - x);
+        }
+        assert_eq!(sqrt_mod_prime(&3u64, &p), None);
+
+        // the largest prime below 2^64 is 3 mod 4
+        let p = u64::MAX - 58;
+        let x = 0x0123_4567_89ab_cdefu64;
+        let root = sqrt_mod_prime(&x.mul_mod(&x, &p), &p).unwrap();
+        assert!(root == x || root == p - x);
+
+        // 2^255 - 19 is 1 mod 4 and the P-256 prime is 3 mod 4
+        for hex in ["7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed", "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff"] {
+            let p = BigUint::from_hex(hex).unwrap();
+            let x = BigUint::from_hex("6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296").unwrap();
+            let square = &x * &x % &p;
+            let root = sqrt_mod_prime(&square, &p).unwrap();
+            assert_eq!(&root * &root % &p, square);
+
+            let cube = &square * &x % &p;
+            let root = cbrt_mod_prime(&cube, &p).unwrap();
+            assert_eq!(&root * &root % &p * &root % &p, cube);
+        }
+
+        // 1000000009 is 1 mod 3 and 3^2 divides p - 1
+        let p = 1_000_000_009u64;
+        let x = 987_654_321u64;
+        let cube = x.pow_mod(&3, &p);
+        assert_eq!(cbrt_mod_prime(&cube, &p).unwrap().pow_mod(&3, &p), cube);
+
+        // 2 is a cube exactly when 2^((p-1)/3) = 1
+        let is_cube = 2u64.pow_mod(&((p - 1) / 3), &p) == 1;
+        assert_eq!(cbrt_mod_prime(&2, &p).is_some(), is_cube);
+    }
+
+    #[test]
+    fn run_fp_roots() {
+        type F13 = Fp<13>;
+        assert!(F13::new(10).is_square());
+        assert!(!F13::new(5).is_square());
+        assert_eq!(F13::new(10).sqrt(), Some(F13::new(6)));
+        assert_eq!(F13::new(5).sqrt(), None);
+        assert_eq!(F13::new(8).cbrt().map(|r| r * r * r), Some(F13::new(8)));
+        assert_eq!(F13::new(2).cbrt(), None);
+
+        // every element is a cube when p = 2 mod 3
+        type F11 = Fp<11>;
+        for i in 0..11 {
+            let root = F11::new(i).cbrt().unwrap();
+            assert_eq!(root * root * root, F11::new(i));
+        }
+    }
+
+}
@@ -0,0 +1,163 @@
+use crate::math::elliptic_curve::{EllipticCurve, Point};
+use crate::math::field::PrimeField;
+use crate::math::group::{Addition, Group};
+use crate::math::integer::UnsignedInt;
+use crate::math::roots::Roots;
+use crate::math::Error;
+
+/// The two SEC1 forms of an affine point
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// 02 or 03 for even or odd y followed by x, 33 bytes for a 256-bit curve
+    Compressed,
+    /// 04 followed by x and y, 65 bytes for a 256-bit curve
+    Uncompressed,
+}
+
+/// Returns the number of bytes in an encoded field element
+pub fn field_len<E: UnsignedInt>(curve: &EllipticCurve<PrimeField<E>, E>) -> usize {
+    curve.field().modulus().bits().div_ceil(8)
+}
+
+/// Encodes the point as in SEC1 section 2.3.3, with the point at infinity as the single byte 00
+pub fn encode_point<E: UnsignedInt>(curve: &EllipticCurve<PrimeField<E>, E>, point: &Point<E>, encoding: Encoding) -> Vec<u8> {
+    let len = field_len(curve);
+    let (x, y) = match point {
+        Point::Infinity => return vec![0],
+        Point::Affine { x, y } => (x, y),
+    };
+
+    let mut bytes = match encoding {
+        Encoding::Compressed => vec![if y.is_even() { 0x02 } else { 0x03 }],
+        Encoding::Uncompressed => vec![0x04],
+    };
+    bytes.extend(to_bytes_be(x, len));
+    if encoding == Encoding::Uncompressed {
+        bytes.extend(to_bytes_be(y, len));
+    }
+    bytes
+}
+
+/// Decodes a point as in SEC1 section 2.3.4.
+///
+/// Returns InvalidEncoding for an unknown prefix, the wrong length or a coordinate which is not
+/// reduced mod p, and NotOnCurve when the coordinates do not satisfy the curve equation or a
+/// compressed x has no point above it with the given parity.
+pub fn decode_point<E: UnsignedInt>(curve: &EllipticCurve<PrimeField<E>, E>, bytes: &[u8]) -> Result<Point<E>, Error> {
+    let len = field_len(curve);
+    let field = curve.field();
+    let element = |bytes: &[u8]| {
+        let e = from_bytes_be::<E>(bytes);
+        if e >= *field.modulus() {
+            return Err(Error::InvalidEncoding);
+        }
+        Ok(e)
+    };
+
+    match bytes {
+        [0x00] => Ok(Point::Infinity),
+        [0x04, rest @ ..] if rest.len() == 2 * len => {
+            let (x, y) = rest.split_at(len);
+            curve.point(element(x)?, element(y)?)
+        }
+        [prefix @ (0x02 | 0x03), rest @ ..] if rest.len() == len => {
+            // the two roots of x^3 + ax + b are y and p - y which differ in parity as p is odd,
+            // except for y = 0 which is even so there is no point above x with an odd prefix
+            let x = element(rest)?;
+            let y = field.sqrt(curve.rhs(&x)).ok_or(Error::NotOnCurve)?;
+            let even = *prefix == 0x02;
+            if y.is_zero() && !even {
+                return Err(Error::NotOnCurve);
+            }
+            let y = if y.is_even() == even { y } else { <PrimeField<E> as Group<Addition, E>>::inverse(field, y) };
+            Ok(Point::Affine { x, y })
+        }
+        _ => Err(Error::InvalidEncoding),
+    }
+}
+
+/// Encodes the point as a lowercase hex string
+pub fn encode_point_hex<E: UnsignedInt>(curve: &EllipticCurve<PrimeField<E>, E>, point: &Point<E>, encoding: Encoding) -> String {
+    hex::encode(encode_point(curve, point, encoding))
+}
+
+/// Decodes a point from a hex string, returning InvalidEncoding if it is not valid hex
+pub fn decode_point_hex<E: UnsignedInt>(curve: &EllipticCurve<PrimeField<E>, E>, s: &str) -> Result<Point<E>, Error> {
+    let bytes = hex::decode(s).map_err(|_| Error::InvalidEncoding)?;
+    decode_point(curve, &bytes)
+}
+
+/// Returns the big endian bytes of e padded with leading zeros to len bytes
+fn to_bytes_be<E: UnsignedInt>(e: &E, len: usize) -> Vec<u8> {
+    (0..len).rev().map(|i| (0..8).rev().fold(0u8, |byte, j| byte << 1 | e.bit(8 * i + j) as u8)).collect()
+}
+
+fn from_bytes_be<E: UnsignedInt>(bytes: &[u8]) -> E {
+    bytes.iter().fold(E::zero(), |e, &byte| (e << 8) + E::from_u32(byte as u32))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn run_round_trip() {
+        // y^2 = x^3 + 2x + 3 mod 1009 so field elements take two bytes
+        let curve = EllipticCurve::new(PrimeField::new(1009u64), 2, 3);
+        assert_eq!(field_len(&curve), 2);
+
+        let mut count = 1;
+        for x in 0..1009 {
+            for y in 0..1009 {
+                let Ok(point) = curve.point(x, y) else { continue };
+                count += 1;
+                for encoding in [Encoding::Compressed, Encoding::Uncompressed] {
+                    let bytes = encode_point(&curve, &point, encoding);
+                    assert_eq!(decode_point(&curve, &bytes), Ok(point.clone()));
+                    assert_eq!(decode_point_hex(&curve, &encode_point_hex(&curve, &point, encoding)), Ok(point.clone()));
+                }
+            }
+        }
+        assert_eq!(count, 1068);
+
+        // 6^2 = 3^3 + 2.3 + 3 and the other root 1003 is odd
+        let point = curve.point(3, 6).unwrap();
+        assert_eq!(encode_point_hex(&curve, &point, Encoding::Uncompressed), "0400030006");
+        assert_eq!(encode_point_hex(&curve, &point, Encoding::Compressed), "020003");
+        assert_eq!(decode_point_hex(&curve, "030003"), Ok(Point::Affine { x: 3, y: 1003 }));
+        assert_eq!(encode_point(&curve, &Point::Infinity, Encoding::Compressed), vec![0]);
+        assert_eq!(decode_point(&curve, &[0]), Ok(Point::Infinity));
+    }
+
+    #[test]
+    fn run_invalid_encodings() {
+        let curve = EllipticCurve::new(PrimeField::new(1009u64), 2, 3);
+        let (x, y) = (0..1009u64).find_map(|x| (0..1009).find(|&y| curve.point(x, y).is_ok()).map(|y| (x, y))).unwrap();
+        let bytes = encode_point(&curve, &Point::Affine { x, y }, Encoding::Uncompressed);
+
+        assert_eq!(decode_point(&curve, &bytes[..4]), Err(Error::InvalidEncoding));
+        assert_eq!(decode_point(&curve, &[]), Err(Error::InvalidEncoding));
+        assert_eq!(decode_point(&curve, &[0x05, 0, 1, 0, 1]), Err(Error::InvalidEncoding));
+        assert_eq!(decode_point(&curve, &[0x00, 0x00]), Err(Error::InvalidEncoding));
+        assert_eq!(decode_point_hex(&curve, "04zz"), Err(Error::InvalidEncoding));
+
+        // 1009 = 0x03f1 is not reduced
+        assert_eq!(decode_point(&curve, &[0x02, 0x03, 0xf1]), Err(Error::InvalidEncoding));
+
+        // off the curve
+        let mut off = bytes.clone();
+        off[4] ^= 1;
+        assert_eq!(decode_point(&curve, &off), Err(Error::NotOnCurve));
+
+        // an x with no point above it has no square root of x^3 + ax + b
+        let x = (0..1009u64).find(|x| curve.field().sqrt(curve.rhs(x)).is_none()).unwrap();
+        assert_eq!(decode_point(&curve, &[0x02, (x >> 8) as u8, x as u8]), Err(Error::NotOnCurve));
+
+        // y^2 = x^3 - x mod 1009 has the point (1, 0) whose only root is even
+        let curve = EllipticCurve::new(PrimeField::new(1009u64), 1008, 0);
+        assert_eq!(decode_point(&curve, &[0x02, 0x00, 0x01]), Ok(Point::Affine { x: 1, y: 0 }));
+        assert_eq!(decode_point(&curve, &[0x03, 0x00, 0x01]), Err(Error::NotOnCurve));
+    }
+
+}
@@ -0,0 +1,190 @@
+use crate::math::euclid::{self, gcd};
+use crate::math::group::{AbelianGroup, Group, Monoid, Multiplication};
+use crate::math::integer::UnsignedInt;
+use crate::math::number_theory::{carmichael, multiplicative_order, totient};
+use crate::math::Error;
+
+/// The units mod n, the integers mod n which are coprime to n, form an abelian group under
+/// multiplication often written Z_n^*.
+///
+/// Unlike NaturalNumbersModN every element has an inverse, which is found with the extended
+/// Euclidean algorithm. The group has φ(n) elements so by Lagrange's theorem a^φ(n) = 1 for
+/// every unit a, which is Euler's theorem and the reason RSA decryption works.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitsModN<E>(E);
+
+impl<E: UnsignedInt> UnitsModN<E> {
+
+    pub fn new(modulus: E) -> Self {
+        match UnitsModN::try_new(modulus) {
+            Ok(group) => group,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Returns an error rather than panicking if the modulus is zero
+    pub fn try_new(modulus: E) -> Result<Self, Error> {
+        if modulus.is_zero() {
+            return Err(Error::ZeroModulus);
+        }
+
+        Ok(UnitsModN(modulus))
+    }
+
+    /// Returns the modulus n
+    pub fn modulus(&self) -> &E {
+        &self.0
+    }
+
+    /// Returns true if e is a reduced residue coprime to n
+    pub fn contains(&self, e: &E) -> bool {
+        *e < self.0 && gcd(e.clone(), self.0.clone()).is_one()
+    }
+
+    /// Reduces e mod n and returns it if it is a unit
+    pub fn element(&self, e: E) -> Result<E, Error> {
+        let e = e % self.0.clone();
+        if e.is_zero() && !self.0.is_one() {
+            return Err(Error::DivisionByZero);
+        }
+        if !self.contains(&e) {
+            return Err(Error::NotInvertible);
+        }
+        Ok(e)
+    }
+
+    /// Returns every unit in increasing order which is only practical for small moduli
+    pub fn elements(&self) -> Vec<E> {
+        let mut elements = Vec::new();
+        let mut e = E::zero();
+        while e < self.0 {
+            if self.contains(&e) {
+                elements.push(e.clone());
+            }
+            e = e + E::one();
+        }
+        elements
+    }
+
+    /// Returns the number of units which is Euler's totient φ(n)
+    pub fn order(&self) -> E {
+        totient(&self.0)
+    }
+
+    /// Returns the exponent of the group, the smallest m with a^m = 1 for every unit, which is
+    /// Carmichael's function λ(n). The group is cyclic exactly when this equals the order.
+    pub fn exponent(&self) -> E {
+        carmichael(&self.0)
+    }
+
+    /// Returns the order of e or None if e is not a unit
+    pub fn order_of(&self, e: &E) -> Option<E> {
+        if !self.contains(e) {
+            return None;
+        }
+        multiplicative_order(e, &self.0)
+    }
+}
+
+impl<E: UnsignedInt> Monoid<Multiplication, E> for UnitsModN<E> {
+    fn apply(&self, e1: E, e2: E) -> E {
+        e1.mul_mod(&e2, &self.0)
+    }
+
+    fn identity(&self) -> E {
+        E::one() % self.0.clone()
+    }
+}
+
+impl<E: UnsignedInt> Group<Multiplication, E> for UnitsModN<E> {
+    fn inverse(&self, e: E) -> E {
+        match self.checked_inverse(e) {
+            Ok(inverse) => inverse,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    fn checked_inverse(&self, e: E) -> Result<E, Error> {
+        let e = self.element(e)?;
+        euclid::mod_inverse(e, self.0.clone()).ok_or(Error::NotInvertible)
+    }
+}
+
+impl<E: UnsignedInt> AbelianGroup<Multiplication, E> for UnitsModN<E> {}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::math::laws::{check_abelian_group, Elements};
+
+    #[test]
+    fn run_units_mod_n() {
+        let group = UnitsModN::new(15u32);
+        assert_eq!(group.elements(), vec![1, 2, 4, 7, 8, 11, 13, 14]);
+        assert_eq!(group.order(), 8);
+        assert_eq!(group.exponent(), 4);
+        assert!(group.contains(&7));
+        assert!(!group.contains(&6));
+        assert!(!group.contains(&16));
+
+        assert_eq!(group.apply(7, 13), 1);
+        assert_eq!(group.inverse(7), 13);
+        assert_eq!(group.checked_inverse(22), Ok(13));
+        assert_eq!(group.checked_inverse(6), Err(Error::NotInvertible));
+        assert_eq!(group.checked_inverse(0), Err(Error::DivisionByZero));
+        assert_eq!(group.order_of(&2), Some(4));
+        assert_eq!(group.order_of(&5), None);
+
+        assert_eq!(UnitsModN::try_new(0u32).err(), Some(Error::ZeroModulus));
+        assert_eq!(UnitsModN::new(1u32).elements(), vec![0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "No inverse found")]
+    fn run_units_mod_n_inverse_of_non_unit() {
+        UnitsModN::new(15u32).inverse(5);
+    }
+
+    #[test]
+    fn run_units_mod_n_axioms() {
+        for n in 1..60u32 {
+            let group = UnitsModN::new(n);
+            let elements = group.elements();
+            assert_eq!(elements.len() as u32, group.order());
+            assert_eq!(check_abelian_group::<Multiplication, _, _>(&group, &mut Elements::All(&elements)), Ok(()), "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn run_eulers_theorem_on_units() {
+        for n in 2..200u64 {
+            let group = UnitsModN::new(n);
+            let (phi, lambda) = (group.order(), group.exponent());
+            for a in group.elements() {
+                assert_eq!(group.pow(a, &phi), 1, "a = {}, n = {}", a, n);
+                assert_eq!(group.pow(a, &lambda), 1, "a = {}, n = {}", a, n);
+                assert_eq!(lambda % group.order_of(&a).unwrap(), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn run_rsa_on_units() {
+        // the textbook key with p = 61, q = 53, e = 17 and d = e^-1 mod λ(n)
+        let group = UnitsModN::new(3233u32);
+        let e = 17u32;
+        let d = euclid::mod_inverse(e, group.exponent()).unwrap();
+        assert_eq!(d, 413);
+
+        for message in group.elements() {
+            let ciphertext = group.pow(message, &e);
+            assert_eq!(group.pow(ciphertext, &d), message);
+        }
+
+        // signing is the same operation with the exponents swapped
+        let signature = group.pow(65, &d);
+        assert_eq!(group.pow(signature, &e), 65);
+    }
+
+}
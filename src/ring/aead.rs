@@ -74,7 +74,7 @@ mod tests {
         println!("data = {}", String::from_utf8(data.to_vec()).unwrap());
 
         // Create a mutable copy of the data that will be encrypted in place
-        let mut in_out = data.clone();
+        let mut in_out = *data;
 
         // Encrypt the data with AEAD using the AES_256_GCM algorithm
         let tag = sealing_key.seal_in_place_separate_tag(associated_data, &mut in_out)?;
@@ -202,7 +202,7 @@ mod tests {
 
         let associated_data = Aad::from(b"additional public data");
         let data = b"hello world";
-        let mut in_out = data.clone();
+        let mut in_out = *data;
         let tag = sealing_key.seal_in_place_separate_tag(associated_data, &mut in_out)?;
 
         let unbound_key = UnboundKey::new(&AES_256_GCM, &key_bytes)?;
@@ -48,7 +48,7 @@ mod tests {
         }
 
         // Verify the integrity of the message - success case
-        hmac::verify(&key, &msg_data.as_ref(), tag.as_ref()).unwrap();
+        hmac::verify(&key, msg_data.as_ref(), tag.as_ref()).unwrap();
     }
 }
 
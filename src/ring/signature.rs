@@ -13,6 +13,10 @@ mod tests {
     use ring::signature::Ed25519KeyPair;
     use ring::signature::EcdsaKeyPair;
     use ring::signature::UnparsedPublicKey;
+    use ring::signature::{EcdsaSigningAlgorithm, ECDSA_P384_SHA384_ASN1_SIGNING};
+    use crate::math::BigUint;
+    use crate::math::named_curves::{CurveParams, P256, P384};
+    use crate::math::sec1::{decode_point, encode_point, Encoding};
 
     #[test]
     fn run_ecdsa() -> Result<(), Unspecified> {
@@ -33,6 +37,54 @@ mod tests {
         peer_public_key.verify(MESSAGE, sig.as_ref())
     }
 
+    #[test]
+    fn run_ecdsa_public_key_is_a_curve_point() -> Result<(), Unspecified> {
+        let rand = SystemRandom::new();
+        let pkcs8_bytes = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING,&rand)?;
+        let key_pair = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING,pkcs8_bytes.as_ref(), &rand).map_err(|_| Unspecified)?;
+
+        // the public key is an uncompressed SEC1 point on P-256
+        let curve = P256.curve();
+
+        let public_key_bytes = key_pair.public_key().as_ref();
+        assert_eq!(public_key_bytes.len(), 65);
+        let point = decode_point(&curve, public_key_bytes).map_err(|_| Unspecified)?;
+        assert!(curve.is_on_curve(&point));
+        assert_eq!(encode_point(&curve, &point, Encoding::Uncompressed), public_key_bytes);
+
+        // the compressed form decompresses to the same point
+        let compressed = encode_point(&curve, &point, Encoding::Compressed);
+        assert_eq!(compressed.len(), 33);
+        assert_eq!(decode_point(&curve, &compressed), Ok(point));
+        Ok(())
+    }
+
+    #[test]
+    fn run_ecdsa_public_key_from_private_scalar() -> Result<(), Unspecified> {
+        let algorithms: [(&'static EcdsaSigningAlgorithm, CurveParams); 2] = [(&ECDSA_P256_SHA256_ASN1_SIGNING, P256), (&ECDSA_P384_SHA384_ASN1_SIGNING, P384)];
+        for (alg, params) in algorithms {
+            let rand = SystemRandom::new();
+            let pkcs8_bytes = EcdsaKeyPair::generate_pkcs8(alg, &rand)?;
+            let key_pair = EcdsaKeyPair::from_pkcs8(alg, pkcs8_bytes.as_ref(), &rand).map_err(|_| Unspecified)?;
+
+            // the public key is d.G for the private scalar d
+            let curve = params.curve();
+            let d = private_scalar(pkcs8_bytes.as_ref()).ok_or(Unspecified)?;
+            assert!(d < params.order());
+            let public_key = curve.multiply(&params.generator(), &d);
+            assert_eq!(encode_point(&curve, &public_key, Encoding::Uncompressed), key_pair.public_key().as_ref(), "{}", params.name);
+        }
+        Ok(())
+    }
+
+    /// Returns the private scalar from the ECPrivateKey inside a PKCS#8 document, the octet string
+    /// following the version number 1
+    fn private_scalar(pkcs8: &[u8]) -> Option<BigUint> {
+        let start = pkcs8.windows(4).position(|w| w[..3] == [0x02, 0x01, 0x01] && w[3] == 0x04)? + 4;
+        let len = *pkcs8.get(start)? as usize;
+        pkcs8.get(start + 1..start + 1 + len).map(BigUint::from_bytes_be)
+    }
+
     #[test]
     fn run_eddsa() -> Result<(), Unspecified> {
         // generate a new Ed25519 key pair